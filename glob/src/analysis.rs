@@ -0,0 +1,426 @@
+//! Decision procedures for the languages of globs.
+//!
+//! Each glob is translated into a nondeterministic finite automaton that reads
+//! a path from start to end, with the components joined by `/`.
+//! The automaton mirrors what the (end-to-start) matching engine accepts,
+//! including where the engine lets a `/` in a pattern match the start of a path.
+//! Questions about two globs are then answered by exploring the product of their automata,
+//! restricted to well-formed paths.
+
+use alloc::borrow::ToOwned;
+use alloc::collections::{BTreeSet, VecDeque};
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::number::NumRange;
+use super::{FlatSet, Glob, Matcher};
+
+/// What the model knows about the character before the current position.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Prev {
+    Start,
+    Sep,
+    Other,
+}
+
+impl Prev {
+    pub fn of(c: char) -> Prev {
+        match c {
+            '/' => Prev::Sep,
+            _ => Prev::Other,
+        }
+    }
+
+    /// Returns `true` if a path may end here, i.e. if the last component is not empty.
+    fn can_end(self) -> bool {
+        !matches!(self, Prev::Start | Prev::Sep)
+    }
+
+    /// Returns `true` if a path may continue with `c`.
+    fn can_read(self, c: char) -> bool {
+        c != '/' || self.can_end()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Guard {
+    /// At the start of the path.
+    Start,
+    /// At the start of a path component.
+    Boundary,
+}
+
+impl Guard {
+    fn allows(self, prev: Prev) -> bool {
+        match self {
+            Guard::Start => prev == Prev::Start,
+            Guard::Boundary => matches!(prev, Prev::Start | Prev::Sep),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Class<'a> {
+    Char(char),
+    /// An ASCII digit.
+    Digit,
+    NotSep,
+    Any,
+    Set(&'a FlatSet<char>, bool),
+}
+
+impl Class<'_> {
+    fn contains(self, c: char) -> bool {
+        match self {
+            Class::Char(x) => c == x,
+            Class::Digit => c.is_ascii_digit(),
+            Class::NotSep => c != '/',
+            Class::Any => true,
+            Class::Set(set, should_have) => c != '/' && set.contains(c) == should_have,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Edge<'a> {
+    Epsilon(usize),
+    Guard(Guard, usize),
+    Char(Class<'a>, usize),
+}
+
+/// An automaton with a start state of `0`.
+pub struct Nfa<'a> {
+    edges: Vec<Vec<Edge<'a>>>,
+    pub accept: usize,
+}
+
+impl<'a> Nfa<'a> {
+    pub fn new(glob: &'a Glob) -> Self {
+        let mut nfa = Nfa {
+            edges: vec![Vec::new()],
+            accept: 0,
+        };
+        nfa.accept = match glob.0.split_first() {
+            // Patterns without a `/` may match after any directory.
+            Some((Matcher::Sep, rest)) => {
+                let prefix = nfa.repeat(0, Class::Any);
+                let sep = nfa.sep(prefix);
+                nfa.seq(rest, sep)
+            }
+            Some(_) => nfa.seq(glob.0.as_slice(), 0),
+            None => nfa.repeat(0, Class::Any),
+        };
+        nfa
+    }
+
+    fn state(&mut self) -> usize {
+        self.edges.push(Vec::new());
+        self.edges.len() - 1
+    }
+
+    fn epsilon(&mut self, from: usize, to: usize) {
+        self.edges[from].push(Edge::Epsilon(to));
+    }
+
+    fn guard(&mut self, from: usize, guard: Guard) -> usize {
+        let to = self.state();
+        self.edges[from].push(Edge::Guard(guard, to));
+        to
+    }
+
+    fn char(&mut self, from: usize, class: Class<'a>) -> usize {
+        let to = self.state();
+        self.edges[from].push(Edge::Char(class, to));
+        to
+    }
+
+    fn repeat(&mut self, from: usize, class: Class<'a>) -> usize {
+        let to = self.state();
+        self.epsilon(from, to);
+        self.edges[to].push(Edge::Char(class, to));
+        to
+    }
+
+    /// The engine matches `/` against a component boundary,
+    /// which is either a `/` in the path or the start of the path.
+    fn sep(&mut self, from: usize) -> usize {
+        let slash = self.char(from, Class::Char('/'));
+        self.epsilon(from, slash);
+        self.guard(slash, Guard::Boundary)
+    }
+
+    fn seq(&mut self, matchers: &'a [Matcher], mut from: usize) -> usize {
+        for matcher in matchers {
+            from = self.matcher(matcher, from);
+        }
+        from
+    }
+
+    fn matcher(&mut self, matcher: &'a Matcher, from: usize) -> usize {
+        match matcher {
+            Matcher::End => self.guard(from, Guard::Start),
+            Matcher::Sep => self.sep(from),
+            Matcher::AnySeq(false) => self.repeat(from, Class::NotSep),
+            Matcher::AnySeq(true) => self.repeat(from, Class::Any),
+            Matcher::AnyChar => self.char(from, Class::NotSep),
+            Matcher::Suffix(s) => s
+                .chars()
+                .fold(from, |from, c| self.char(from, Class::Char(c))),
+            Matcher::CharClass(set, should_have) => self.char(from, Class::Set(set, *should_have)),
+            Matcher::Range(range) => self.range(from, range),
+            Matcher::Any(options) => {
+                let to = self.state();
+                for option in options.as_slice() {
+                    let end = self.seq(option.0.as_slice(), from);
+                    self.epsilon(end, to);
+                }
+                to
+            }
+        }
+    }
+
+    /// Adds a path for each way of writing a number in `range`.
+    fn range(&mut self, from: usize, range: &NumRange) -> usize {
+        let to = self.state();
+        let (lower, upper) = (range.lower(), range.upper());
+        if !upper.is_negative() {
+            let low = if lower.is_negative() {
+                "0"
+            } else {
+                lower.digits()
+            };
+            self.magnitudes(from, low, upper.digits(), range.width(), to);
+        }
+        if lower.is_negative() {
+            // Zero is never written with a `-`.
+            let low = if upper.is_negative() {
+                upper.digits()
+            } else {
+                "1"
+            };
+            let minus = self.char(from, Class::Char('-'));
+            self.magnitudes(minus, low, lower.digits(), range.width(), to);
+        }
+        to
+    }
+
+    /// Adds paths from `from` to `to` for the digits of the numbers from `low` to `high`,
+    /// zero-padded to `width` digits if provided.
+    fn magnitudes(&mut self, from: usize, low: &str, high: &str, width: Option<usize>, to: usize) {
+        if let Some(width) = width {
+            let pad = |digits: &str| format!("{:0>1$}", digits, width);
+            let end = self.digits(from, &pad(low), &pad(high));
+            self.epsilon(end, to);
+            return;
+        }
+        for len in low.len()..=high.len() {
+            let first = if len == low.len() {
+                low.to_owned()
+            } else {
+                format!("1{}", "0".repeat(len - 1))
+            };
+            let last = if len == high.len() {
+                high.to_owned()
+            } else {
+                "9".repeat(len)
+            };
+            let end = self.digits(from, &first, &last);
+            self.epsilon(end, to);
+        }
+    }
+
+    /// Adds a path for the strings of digits between `low` and `high`,
+    /// which have the same length.
+    fn digits(&mut self, from: usize, low: &str, high: &str) -> usize {
+        let (first, last) = match (low.chars().next(), high.chars().next()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return from,
+        };
+        let (low, high) = (&low[1..], &high[1..]);
+        if first == last {
+            let next = self.char(from, Class::Char(first));
+            return self.digits(next, low, high);
+        }
+        // Digits after the first are unconstrained unless they're at the edges of the range.
+        let any_after = |c: char| {
+            (c != first || low.bytes().all(|b| b == b'0'))
+                && (c != last || high.bytes().all(|b| b == b'9'))
+        };
+        let to = self.state();
+        for c in first..=last {
+            let next = self.char(from, Class::Char(c));
+            let end = if any_after(c) {
+                (0..low.len()).fold(next, |from, _| self.char(from, Class::Digit))
+            } else if c == first {
+                self.digits(next, low, &"9".repeat(low.len()))
+            } else {
+                self.digits(next, &"0".repeat(high.len()), high)
+            };
+            self.epsilon(end, to);
+        }
+        to
+    }
+
+    /// Adds the states reachable from `states` without reading a character.
+    pub fn close(&self, states: &mut Vec<usize>, prev: Prev) {
+        let mut idx = 0;
+        while let Some(&state) = states.get(idx) {
+            for edge in &self.edges[state] {
+                let next = match *edge {
+                    Edge::Epsilon(next) => next,
+                    Edge::Guard(guard, next) if guard.allows(prev) => next,
+                    _ => continue,
+                };
+                if !states.contains(&next) {
+                    states.push(next);
+                }
+            }
+            idx += 1;
+        }
+        states.sort_unstable();
+    }
+
+    /// Returns the states reachable from `states` by reading `c`.
+    pub fn read(&self, states: &[usize], c: char) -> Vec<usize> {
+        let mut next: Vec<usize> = states
+            .iter()
+            .flat_map(|&state| self.edges[state].iter())
+            .filter_map(|edge| match *edge {
+                Edge::Char(class, next) if class.contains(c) => Some(next),
+                _ => None,
+            })
+            .collect();
+        next.sort_unstable();
+        next.dedup();
+        self.close(&mut next, Prev::of(c));
+        next
+    }
+
+    /// Returns the successors of `state` that don't require reading a character.
+    fn silent(&self, state: usize, prev: Prev) -> impl Iterator<Item = usize> + '_ {
+        self.edges[state]
+            .iter()
+            .filter_map(move |edge| match *edge {
+                Edge::Epsilon(next) => Some(next),
+                Edge::Guard(guard, next) if guard.allows(prev) => Some(next),
+                _ => None,
+            })
+    }
+
+    /// Returns the classes of characters that `state` can read.
+    fn classes(&self, state: usize) -> impl Iterator<Item = (Class<'a>, usize)> + '_ {
+        self.edges[state].iter().filter_map(|edge| match *edge {
+            Edge::Char(class, next) => Some((class, next)),
+            _ => None,
+        })
+    }
+}
+
+/// Splits the characters into ranges that no automaton can tell apart.
+///
+/// Returns the first code point and the first character of each range, in order.
+pub fn alphabet(nfas: &[&Nfa<'_>]) -> Vec<(u32, char)> {
+    let mut bounds: Vec<u32> = vec![0];
+    let mut add = |first: char, last: char| {
+        bounds.push(first as u32);
+        bounds.push(last as u32 + 1);
+    };
+    // The character that guards distinguish.
+    add('/', '/');
+    for edge in nfas.iter().flat_map(|nfa| nfa.edges.iter().flatten()) {
+        match *edge {
+            Edge::Char(Class::Char(c), _) => add(c, c),
+            Edge::Char(Class::Digit, _) => add('0', '9'),
+            Edge::Char(Class::Set(set, _), _) => {
+                for &c in set.as_slice() {
+                    add(c, c);
+                }
+            }
+            _ => (),
+        }
+    }
+    bounds.sort_unstable();
+    bounds.dedup();
+    bounds.push(char::MAX as u32 + 1);
+    bounds
+        .windows(2)
+        .filter_map(|range| {
+            // Skip over the surrogate code points.
+            let start = if (0xD800..0xE000).contains(&range[0]) {
+                0xE000
+            } else {
+                range[0]
+            };
+            if start < range[1] {
+                char::from_u32(start).map(|c| (range[0], c))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Returns `true` if some path is matched by both globs.
+pub fn overlaps(a: &Glob, b: &Glob) -> bool {
+    let (a, b) = (Nfa::new(a), Nfa::new(b));
+    let alphabet = alphabet(&[&a, &b]);
+    let mut seen = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    queue.push_back((0, 0, Prev::Start));
+    while let Some(node) = queue.pop_front() {
+        if !seen.insert(node) {
+            continue;
+        }
+        let (state_a, state_b, prev) = node;
+        if state_a == a.accept && state_b == b.accept && prev.can_end() {
+            return true;
+        }
+        queue.extend(a.silent(state_a, prev).map(|next| (next, state_b, prev)));
+        queue.extend(b.silent(state_b, prev).map(|next| (state_a, next, prev)));
+        for (class_a, next_a) in a.classes(state_a) {
+            for (class_b, next_b) in b.classes(state_b) {
+                for &(_, c) in &alphabet {
+                    if prev.can_read(c) && class_a.contains(c) && class_b.contains(c) {
+                        queue.push_back((next_a, next_b, Prev::of(c)));
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Returns `true` if every path matched by `a` is also matched by `b`.
+pub fn is_subset(a: &Glob, b: &Glob) -> bool {
+    let (a, b) = (Nfa::new(a), Nfa::new(b));
+    let alphabet = alphabet(&[&a, &b]);
+    let mut seen = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    let mut start = vec![0];
+    b.close(&mut start, Prev::Start);
+    queue.push_back((0, start, Prev::Start));
+    // Look for a path that `a` accepts and `b` doesn't,
+    // tracking every state `b` could be in.
+    while let Some(node) = queue.pop_front() {
+        if seen.contains(&node) {
+            continue;
+        }
+        let (state_a, states_b, prev) = &node;
+        if *state_a == a.accept && prev.can_end() && !states_b.contains(&b.accept) {
+            return false;
+        }
+        for next in a.silent(*state_a, *prev) {
+            queue.push_back((next, states_b.clone(), *prev));
+        }
+        for (class, next) in a.classes(*state_a) {
+            for &(_, c) in &alphabet {
+                if prev.can_read(c) && class.contains(c) {
+                    queue.push_back((next, b.read(states_b, c), Prev::of(c)));
+                }
+            }
+        }
+        seen.insert(node);
+    }
+    true
+}
@@ -0,0 +1,271 @@
+//! Deterministic automata for matching paths in time linear in their length.
+//!
+//! These are built from the automata in [`crate::analysis`]
+//! by tracking every state that automaton could be in at once.
+//! They are only built without the `no_std` feature,
+//! as the standard library provides the locking needed to cache them.
+#![cfg_attr(feature = "no_std", allow(dead_code))]
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(not(feature = "no_std"))]
+use std::sync::atomic::{AtomicU32, Ordering};
+#[cfg(not(feature = "no_std"))]
+use std::sync::{Arc, Mutex};
+
+use super::analysis::{alphabet, Nfa, Prev};
+use super::path::{Component, Components};
+use super::{Glob, Matcher};
+
+/// The most transitions a [`Dfa`] may have.
+///
+/// Globs that need more are matched by backtracking instead.
+const MAX_TRANSITIONS: usize = 1 << 16;
+
+/// Returns `true` if an automaton would not match exactly what the engine does.
+fn unsupported(matchers: &[Matcher]) -> bool {
+    matchers.iter().any(|matcher| match matcher {
+        #[cfg(feature = "graphemes")]
+        Matcher::AnyChar | Matcher::CharClass(_, _) => true,
+        Matcher::Any(options) => options
+            .as_slice()
+            .iter()
+            .any(|option| unsupported(&option.0)),
+        _ => false,
+    })
+}
+
+pub struct Dfa {
+    /// The first code point of each range of characters that the automaton treats the same.
+    starts: Vec<u32>,
+    /// The range that each ASCII character is in.
+    ascii: [u32; 128],
+    /// The next state for each state and range, with state `0` being the dead state
+    /// and state `1` being the start state.
+    transitions: Vec<u32>,
+    accepting: Vec<bool>,
+}
+
+impl Dfa {
+    pub fn new(glob: &Glob) -> Option<Dfa> {
+        if unsupported(&glob.0) {
+            return None;
+        }
+        let nfa = Nfa::new(glob);
+        let alphabet = alphabet(&[&nfa]);
+        let mut start = vec![0];
+        nfa.close(&mut start, Prev::Start);
+        // The guards have already been checked for each set,
+        // so the sets alone determine which paths are accepted from here.
+        let mut sets = vec![Vec::new(), start.clone()];
+        let mut ids = BTreeMap::new();
+        ids.insert(start, 1u32);
+        let mut transitions = vec![0u32; alphabet.len()];
+        let mut idx = 1;
+        while let Some(set) = sets.get(idx).cloned() {
+            for &(_, c) in &alphabet {
+                let next = nfa.read(&set, c);
+                let id = if next.is_empty() {
+                    0
+                } else if let Some(&id) = ids.get(&next) {
+                    id
+                } else {
+                    let id = sets.len() as u32;
+                    sets.push(next.clone());
+                    ids.insert(next, id);
+                    id
+                };
+                transitions.push(id);
+            }
+            if transitions.len() > MAX_TRANSITIONS {
+                return None;
+            }
+            idx += 1;
+        }
+        let starts: Vec<u32> = alphabet.iter().map(|&(start, _)| start).collect();
+        let mut ascii = [0u32; 128];
+        for (c, class) in ascii.iter_mut().enumerate() {
+            *class = (starts.partition_point(|&start| start as usize <= c) - 1) as u32;
+        }
+        Some(Dfa {
+            starts,
+            ascii,
+            transitions,
+            accepting: sets.iter().map(|set| set.contains(&nfa.accept)).collect(),
+        })
+    }
+
+    fn step(&self, state: usize, c: char) -> usize {
+        let class = match self.ascii.get(c as usize) {
+            Some(&class) => class as usize,
+            None => self.starts.partition_point(|&start| start <= c as u32) - 1,
+        };
+        self.transitions[state * self.starts.len() + class] as usize
+    }
+
+    /// Returns `true` if the path matches,
+    /// or `None` if the path has no components or isn't valid UTF-8.
+    pub fn matches(&self, path: Components<'_>) -> Option<bool> {
+        let mut state = 1;
+        let mut is_empty = true;
+        for component in path {
+            let text = match component {
+                Component::Name(text) => core::str::from_utf8(text).ok()?,
+                Component::Invalid => return None,
+                // This only occurs at the start of a path.
+                Component::Start => continue,
+            };
+            if !is_empty {
+                state = self.step(state, '/');
+            }
+            is_empty = false;
+            for c in text.chars() {
+                state = self.step(state, c);
+            }
+        }
+        if is_empty {
+            None
+        } else {
+            Some(self.accepting[state])
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+enum Slot {
+    Pending,
+    Unsupported,
+    Ready(Arc<Dfa>),
+}
+
+/// How many times a glob is matched by backtracking before its automaton is built.
+///
+/// Building the automaton costs far more than one match,
+/// so it is only worth it for globs that are matched repeatedly.
+#[cfg(not(feature = "no_std"))]
+const BUILD_AFTER: u32 = 16;
+
+#[cfg(not(feature = "no_std"))]
+struct Lazy {
+    /// The number of matches done by backtracking so far.
+    matches: AtomicU32,
+    slot: Mutex<Slot>,
+}
+
+/// A lazily-built [`Dfa`] for a glob.
+///
+/// With the `no_std` feature there is no way to build one lazily,
+/// so globs are always matched by backtracking.
+///
+/// This compares equal to every other `Cache` so that it doesn't affect comparisons of globs.
+pub struct Cache(#[cfg(not(feature = "no_std"))] Option<Lazy>);
+
+#[cfg(not(feature = "no_std"))]
+impl Cache {
+    /// Returns a cache that never builds an automaton.
+    pub const fn none() -> Cache {
+        Cache(None)
+    }
+
+    pub fn new() -> Cache {
+        Cache::with(0, Slot::Pending)
+    }
+
+    fn with(matches: u32, slot: Slot) -> Cache {
+        Cache(Some(Lazy {
+            matches: AtomicU32::new(matches),
+            slot: Mutex::new(slot),
+        }))
+    }
+
+    /// Returns the automaton for `glob`, building it once `glob` has been matched often enough.
+    pub fn get(&self, glob: &Glob) -> Option<Arc<Dfa>> {
+        let lazy = self.0.as_ref()?;
+        // Checked without locking, as most globs are only matched a few times.
+        if lazy.matches.load(Ordering::Relaxed) < BUILD_AFTER {
+            lazy.matches.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        self.build(glob)
+    }
+
+    /// Returns the automaton for `glob`, building it if it hasn't been built yet.
+    pub fn build(&self, glob: &Glob) -> Option<Arc<Dfa>> {
+        let lazy = self.0.as_ref()?;
+        let mut slot = lazy.slot.lock().ok()?;
+        lazy.matches.store(BUILD_AFTER, Ordering::Relaxed);
+        if let Slot::Pending = *slot {
+            *slot = match Dfa::new(glob) {
+                Some(dfa) => Slot::Ready(Arc::new(dfa)),
+                None => Slot::Unsupported,
+            };
+        }
+        match &*slot {
+            Slot::Ready(dfa) => Some(dfa.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "no_std")]
+impl Cache {
+    pub const fn none() -> Cache {
+        Cache()
+    }
+
+    pub fn new() -> Cache {
+        Cache()
+    }
+
+    #[allow(clippy::unused_self)]
+    pub fn get(&self, _: &Glob) -> Option<&Dfa> {
+        None
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl Clone for Cache {
+    fn clone(&self) -> Self {
+        let lazy = match &self.0 {
+            Some(lazy) => lazy,
+            None => return Cache::none(),
+        };
+        let slot = match lazy.slot.lock() {
+            Ok(slot) => match &*slot {
+                Slot::Ready(dfa) => Slot::Ready(dfa.clone()),
+                Slot::Unsupported => Slot::Unsupported,
+                Slot::Pending => Slot::Pending,
+            },
+            Err(_) => Slot::Pending,
+        };
+        Cache::with(lazy.matches.load(Ordering::Relaxed), slot)
+    }
+}
+
+#[cfg(feature = "no_std")]
+impl Clone for Cache {
+    fn clone(&self) -> Self {
+        Cache()
+    }
+}
+
+impl PartialEq for Cache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Cache {}
+
+impl PartialOrd for Cache {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cache {
+    fn cmp(&self, _: &Self) -> core::cmp::Ordering {
+        core::cmp::Ordering::Equal
+    }
+}
@@ -0,0 +1,247 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::path::{Component, Components};
+use super::stack::Trace;
+use super::{Glob, Matcher};
+
+/// One element of a glob pattern.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Element<'a> {
+    /// The start of the path, which patterns containing a `/` are anchored to.
+    Start,
+    /// A path separator, `/`.
+    Separator,
+    /// Literal text.
+    Literal(&'a str),
+    /// `?`, any one character or extended grapheme cluster.
+    AnyChar,
+    /// `*`, any sequence of characters except `/`.
+    AnySeq,
+    /// `**`, any sequence of characters.
+    AnyPath,
+    /// A character class, e.g. `[a-z]` or `[!a-z]`.
+    CharClass,
+    /// A numeric range, e.g. `{1..42}`.
+    /// Contains the inclusive lower and upper bounds in decimal, without any zero-padding.
+    Range(&'a str, &'a str),
+    /// An alternation, e.g. `{a,b}`. Contains the alternative that was chosen.
+    Alternation(&'a Glob),
+}
+
+impl<'a> Element<'a> {
+    fn new(matcher: &'a Matcher, branch: Option<&'a Glob>) -> Option<Self> {
+        Some(match matcher {
+            Matcher::End => Element::Start,
+            Matcher::Sep => Element::Separator,
+            Matcher::Suffix(s) => Element::Literal(s.as_str()),
+            Matcher::AnyChar => Element::AnyChar,
+            Matcher::AnySeq(false) => Element::AnySeq,
+            Matcher::AnySeq(true) => Element::AnyPath,
+            Matcher::CharClass(_, _) => Element::CharClass,
+            Matcher::Range(range) => Element::Range(range.lower().as_str(), range.upper().as_str()),
+            Matcher::Any(_) => Element::Alternation(branch?),
+        })
+    }
+}
+
+/// The part of a path matched by one wildcard.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Capture<'a> {
+    /// The wildcard.
+    ///
+    /// This is never [`Element::Start`], [`Element::Separator`], or [`Element::Literal`].
+    pub element: Element<'a>,
+    /// The byte range of [`Explanation::path`] that the wildcard matched.
+    pub span: Range<usize>,
+}
+
+/// The reason a path did not match.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Mismatch<'a> {
+    /// The element that failed to match, or `None` if there was nothing to match against.
+    pub element: Option<Element<'a>>,
+    /// The byte offset into [`Explanation::path`] at which `element` failed to match.
+    ///
+    /// Paths are matched from end to start,
+    /// so this is the lowest offset that matching reached before failing.
+    pub position: usize,
+}
+
+/// A detailed result of matching a path against a [`Glob`].
+///
+/// Created by [`Glob::explain`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Explanation<'a> {
+    path: String,
+    result: Result<Vec<Capture<'a>>, Mismatch<'a>>,
+}
+
+impl<'a> Explanation<'a> {
+    /// Returns the path as it was seen by the glob engine.
+    ///
+    /// This is the path's components joined by `/`,
+    /// with any bytes that are not valid UTF-8 replaced with `?`.
+    /// All byte offsets in this explanation refer to this string.
+    pub fn path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    /// Returns `true` if the path matched.
+    pub fn is_match(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    /// Returns the captures for each wildcard in pattern order if the path matched.
+    ///
+    /// The captures for the contents of an alternation directly follow
+    /// the capture for the alternation itself.
+    /// `**` is reported as one capture.
+    pub fn captures(&self) -> Option<&[Capture<'a>]> {
+        self.result.as_ref().ok().map(Vec::as_slice)
+    }
+
+    /// Returns the reason the path did not match, if it didn't.
+    pub fn mismatch(&self) -> Option<&Mismatch<'a>> {
+        self.result.as_ref().err()
+    }
+}
+
+struct Step<'b> {
+    matcher: &'b Matcher,
+    branch: Option<&'b Glob>,
+    consumed: usize,
+}
+
+#[derive(Default)]
+struct Recorder<'b> {
+    steps: Vec<Step<'b>>,
+    failure: Option<(&'b Matcher, usize)>,
+}
+
+impl<'b> Trace<'b> for &mut Recorder<'b> {
+    fn checkpoint(&self) -> usize {
+        self.steps.len()
+    }
+    fn rollback(&mut self, checkpoint: usize) {
+        self.steps.truncate(checkpoint);
+    }
+    fn step(&mut self, matcher: &'b Matcher, branch: Option<&'b Glob>, consumed: usize) {
+        self.steps.push(Step {
+            matcher,
+            branch,
+            consumed,
+        });
+    }
+    fn fail(&mut self, matcher: &'b Matcher, consumed: usize) {
+        if self
+            .failure
+            .map_or(true, |(_, furthest)| consumed > furthest)
+        {
+            self.failure = Some((matcher, consumed));
+        }
+    }
+}
+
+/// Builds the text that the splitter's positions are relative to.
+fn path_text(path: Components<'_>) -> String {
+    let mut bytes = Vec::<u8>::new();
+    for (idx, component) in path.enumerate() {
+        if idx != 0 {
+            bytes.push(b'/');
+        }
+        match component {
+            Component::Name(p) => bytes.extend_from_slice(p),
+            Component::Invalid => bytes.push(b'?'),
+            Component::Start => (),
+        }
+    }
+    // Replace invalid bytes one-for-one so that offsets are preserved.
+    let mut start = 0;
+    while let Err(e) = core::str::from_utf8(&bytes[start..]) {
+        let bad = start + e.valid_up_to();
+        let len = e.error_len().unwrap_or(bytes.len() - bad);
+        for byte in &mut bytes[bad..bad + len] {
+            *byte = b'?';
+        }
+        start = bad + len;
+    }
+    String::from_utf8(bytes).unwrap_or_default()
+}
+
+/// Converts the steps for `matchers` into captures, in reverse pattern order.
+///
+/// `consumed` is the position before the first step and is updated to the position after the last.
+fn collect<'b>(
+    matchers: &'b [Matcher],
+    steps: &mut core::slice::Iter<'_, Step<'b>>,
+    consumed: &mut usize,
+    captures: &mut Vec<(Element<'b>, usize, usize)>,
+) {
+    let mut prev: Option<&Matcher> = None;
+    for matcher in matchers.iter().rev() {
+        let step = match steps.next() {
+            Some(step) => step,
+            None => return,
+        };
+        debug_assert!(core::ptr::eq(step.matcher, matcher));
+        let start = *consumed;
+        if let Matcher::Any(_) = matcher {
+            if let Some(branch) = step.branch {
+                collect(branch.0.as_slice(), steps, consumed, captures);
+                captures.push((Element::Alternation(branch), start, *consumed));
+            }
+        } else {
+            *consumed = step.consumed;
+            match (matcher, prev, captures.last_mut()) {
+                (Matcher::AnySeq(true), Some(Matcher::AnySeq(_)), Some(last)) => {
+                    // Fuse the parts of `**` together.
+                    last.0 = Element::AnyPath;
+                    last.2 = *consumed;
+                }
+                (Matcher::End | Matcher::Sep | Matcher::Suffix(_), _, _) => (),
+                _ => {
+                    if let Some(element) = Element::new(matcher, None) {
+                        captures.push((element, start, *consumed));
+                    }
+                }
+            }
+        }
+        prev = Some(matcher);
+    }
+}
+
+pub fn explain<'b>(path: Components<'_>, glob: &'b Glob) -> Explanation<'b> {
+    let text = path_text(path.clone());
+    let len = text.len();
+    let mut recorder = Recorder::default();
+    let matched = super::Splitter::new(path)
+        .and_then(|splitter| {
+            super::matcher::run(splitter, glob, &mut recorder, usize::MAX).unwrap_or(None)
+        })
+        .is_some();
+    let result = if matched {
+        let mut steps = recorder.steps.iter();
+        let mut raw = Vec::new();
+        collect(glob.0.as_slice(), &mut steps, &mut 0, &mut raw);
+        Ok(raw
+            .into_iter()
+            .rev()
+            .map(|(element, start, end)| Capture {
+                element,
+                span: len.saturating_sub(end)..len.saturating_sub(start),
+            })
+            .collect())
+    } else {
+        let (element, consumed) = match recorder.failure {
+            Some((matcher, consumed)) => (Element::new(matcher, None), consumed),
+            None => (glob.0.last().and_then(|m| Element::new(m, None)), 0),
+        };
+        Err(Mismatch {
+            element,
+            position: len.saturating_sub(consumed),
+        })
+    };
+    Explanation { path: text, result }
+}
@@ -1,4 +1,6 @@
-use std::{borrow::Borrow, collections::BTreeSet};
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
 
 /// Very minimal Vec+binary search set.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
@@ -6,23 +6,67 @@
 //!
 //! Create a [`Glob`] using [`Glob::new`],
 //! then match it against paths with [`Glob::matches`].
+//! A `Glob`'s [`Display`][core::fmt::Display] implementation
+//! writes out the pattern as the parser understood it,
+//! and [`Glob::explain`] can be used to find out why a path did or did not match.
+//! [`Glob::is_subset_of`] and [`Glob::overlaps`] compare the sets of paths that two globs match.
+//! [`Glob::walk`] finds the files under a directory that a glob matches.
+//!
+//! ## `no_std`
+//!
+//! With the `no_std` feature, this crate only needs `alloc`.
+//! Paths are then matched as `/`-separated strings with [`Glob::matches_str`],
+//! always by backtracking, and [`Glob::walk`] is unavailable.
+
+#![cfg_attr(all(feature = "no_std", not(test)), no_std)]
 
+extern crate alloc;
+
+mod analysis;
+mod dfa;
+mod explain;
 mod flatset;
 mod matcher;
+mod number;
 mod parser;
+mod parts;
+mod path;
 mod splitter;
 mod stack;
+#[cfg(not(feature = "no_std"))]
+mod walk;
 
 #[cfg(test)]
 mod tests;
 
+pub use explain::{Capture, Element, Explanation, Mismatch};
+pub use matcher::LimitError;
+pub use parser::{SyntaxError, SyntaxErrorKind};
+pub use parts::{Alternatives, Part, Parts};
+#[cfg(not(feature = "no_std"))]
+pub use walk::Walk;
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
 use flatset::FlatSet;
 use matcher::Matcher;
+use path::Components;
 use splitter::Splitter;
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 /// A single glob pattern.
-pub struct Glob(Vec<Matcher>);
+///
+/// Matching is done by backtracking at first. Once a `Glob` has been matched several times,
+/// a deterministic automaton is built for it where possible and used from then on.
+/// See [`Glob::compile`] for building it up front.
+pub struct Glob(Vec<Matcher>, dfa::Cache);
+
+impl core::fmt::Debug for Glob {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Glob").field(&self.0).finish()
+    }
+}
 
 impl Default for Glob {
     fn default() -> Self {
@@ -30,24 +74,330 @@ impl Default for Glob {
     }
 }
 
+/// Writes a canonical form of the pattern.
+///
+/// The output reflects how the pattern was interpreted by the parser,
+/// with redundant syntax removed and malformed syntax escaped.
+/// Parsing the output with [`Glob::new`] results in a `Glob` that is equal to the original.
+///
+/// ```
+/// use ec4rs_glob::Glob;
+/// assert_eq!(Glob::new("{b,a,b}.[c-e]").to_string(), "{a,b}.[c-e]");
+/// assert_eq!(Glob::new("foo/[bar").to_string(), "/foo/\\[bar");
+/// ```
+impl core::fmt::Display for Glob {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut matchers = self.0.iter();
+        // The leading Sep of a pattern without slashes is implicit.
+        if let Some(Matcher::Sep) = self.0.first() {
+            matchers.next();
+        }
+        matchers.try_for_each(|matcher| matcher.fmt(f))
+    }
+}
+
+/// Serializes the pattern as its [`Display`][core::fmt::Display] output,
+/// which deserializing parses again with [`Glob::new`].
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// use ec4rs_glob::Glob;
+/// let glob = Glob::new("*.{toml,rs}");
+/// let json = serde_json::to_string(&glob).unwrap();
+/// assert_eq!(json, r#""*.{rs,toml}""#);
+/// let loaded: Glob = serde_json::from_str(&json).unwrap();
+/// assert_eq!(loaded, glob);
+/// assert!(loaded.matches_str("src/lib.rs"));
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for Glob {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Glob {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = alloc::string::String::deserialize(deserializer)?;
+        Ok(Glob::new(&pattern))
+    }
+}
+
 impl Glob {
     /// Returns an empty `Glob`.
     pub const fn empty() -> Glob {
-        Self(Vec::new())
+        Self(Vec::new(), dfa::Cache::none())
     }
 
     /// Parses the provided pattern.
     ///
     /// This crate attempts to be maximally permissive in terms of accepted input and will treat
     /// common syntax errors, such as unclosed brackets, as if they were escaped.
+    ///
+    /// See [`Glob::new_strict`] and [`Glob::new_with_errors`] for ways to detect such errors.
     pub fn new(pattern: &str) -> Glob {
-        parser::parse(pattern)
+        parser::parse(pattern, &mut parser::Errors::default())
+    }
+
+    /// Parses the provided pattern, failing if it contains any syntax errors.
+    ///
+    /// Returns the first syntax error in the pattern on failure.
+    /// Patterns that parse successfully are parsed identically to [`Glob::new`].
+    ///
+    /// ```
+    /// use ec4rs_glob::{Glob, SyntaxErrorKind};
+    /// assert!(Glob::new_strict("*.{rs,toml}").is_ok());
+    /// let error = Glob::new_strict("*.{rs,toml").unwrap_err();
+    /// assert_eq!(error.kind, SyntaxErrorKind::UnclosedAlternation);
+    /// assert_eq!(error.span, 2..10);
+    /// ```
+    pub fn new_strict(pattern: &str) -> Result<Glob, SyntaxError> {
+        let (glob, errors) = Self::new_with_errors(pattern);
+        match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(glob),
+        }
+    }
+
+    /// Parses the provided pattern as [`Glob::new`] does,
+    /// additionally returning every syntax error that had to be worked around.
+    ///
+    /// The errors are ordered by where they appear in the pattern.
+    pub fn new_with_errors(pattern: &str) -> (Glob, Vec<SyntaxError>) {
+        let mut errors = parser::Errors::default();
+        let glob = parser::parse(pattern, &mut errors);
+        (glob, errors.into_sorted())
     }
 
     /// Returns `true` if the provided path matches this pattern.
+    #[cfg(not(feature = "no_std"))]
     #[must_use]
     pub fn matches(&self, path: impl AsRef<std::path::Path>) -> bool {
-        matcher::matches(path.as_ref(), self).is_some()
+        self.matches_components(Components::from_path(path.as_ref()))
+    }
+
+    /// Returns `true` if the provided `/`-separated path matches this pattern.
+    ///
+    /// Empty and `.` components are ignored, as [`Path::components`][std::path::Path::components]
+    /// does on every platform, so this agrees with [`Glob::matches`] for such paths.
+    ///
+    /// ```
+    /// use ec4rs_glob::Glob;
+    /// let glob = Glob::new("src/*.rs");
+    /// assert!(glob.matches_str("/src/lib.rs"));
+    /// assert!(glob.matches_str("./src//lib.rs"));
+    /// assert!(!glob.matches_str("/project/src/lib.rs"));
+    /// ```
+    #[must_use]
+    pub fn matches_str(&self, path: &str) -> bool {
+        self.matches_components(Components::from_str(path))
+    }
+
+    /// Builds the deterministic automaton used for matching now,
+    /// instead of after this `Glob` has been matched several times.
+    ///
+    /// This is worth doing for globs that will be matched against many paths.
+    /// Returns `false` if this `Glob` can't be matched with an automaton,
+    /// in which case it is always matched by backtracking.
+    ///
+    /// ```
+    /// use ec4rs_glob::Glob;
+    /// let glob = Glob::new("src/**/*.rs");
+    /// assert!(glob.compile());
+    /// assert!(glob.matches_str("src/glob/mod.rs"));
+    /// ```
+    #[cfg(not(feature = "no_std"))]
+    pub fn compile(&self) -> bool {
+        self.1.build(self).is_some()
+    }
+
+    fn matches_components(&self, path: Components<'_>) -> bool {
+        if let Some(matched) = self.1.get(self).and_then(|dfa| dfa.matches(path.clone())) {
+            return matched;
+        }
+        matcher::matches(path, self).is_some()
+    }
+
+    /// As [`Glob::matches`], but gives up after `limit` steps of matching.
+    ///
+    /// Matching by backtracking can take time exponential in the number of wildcards
+    /// for some patterns. Each step tries one element of the pattern at one position in the path.
+    /// Paths that are matched with a deterministic automaton never take too many steps.
+    ///
+    /// ```
+    /// use ec4rs_glob::Glob;
+    /// let glob = Glob::new("*.rs");
+    /// assert_eq!(glob.try_matches("/src/lib.rs", 100), Ok(true));
+    /// ```
+    #[cfg(not(feature = "no_std"))]
+    pub fn try_matches(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        limit: usize,
+    ) -> Result<bool, LimitError> {
+        self.try_matches_components(Components::from_path(path.as_ref()), limit)
+    }
+
+    /// As [`Glob::try_matches`], but for a `/`-separated path as in [`Glob::matches_str`].
+    pub fn try_matches_str(&self, path: &str, limit: usize) -> Result<bool, LimitError> {
+        self.try_matches_components(Components::from_str(path), limit)
+    }
+
+    fn try_matches_components(
+        &self,
+        path: Components<'_>,
+        limit: usize,
+    ) -> Result<bool, LimitError> {
+        if let Some(matched) = self.1.get(self).and_then(|dfa| dfa.matches(path.clone())) {
+            return Ok(matched);
+        }
+        matcher::matches_limited(path, self, limit).map(|matched| matched.is_some())
+    }
+
+    /// Returns `false` if no path under the directory `dir` can match this pattern.
+    ///
+    /// This can be used to skip directories when searching for matching paths.
+    /// It considers the leading path components of the pattern and any literal text
+    /// at the start of a component containing `**`, as [`Glob::walk`] does,
+    /// so it may return `true` for directories that turn out not to contain matches.
+    ///
+    /// ```
+    /// use ec4rs_glob::Glob;
+    /// let glob = Glob::new("src/**/*.rs");
+    /// assert!(glob.may_match_under("src/glob"));
+    /// assert!(!glob.may_match_under("tests"));
+    /// assert!(Glob::new("*.rs").may_match_under("tests"));
+    /// ```
+    #[cfg(not(feature = "no_std"))]
+    #[must_use]
+    pub fn may_match_under(&self, dir: impl AsRef<std::path::Path>) -> bool {
+        walk::may_match_under(self, dir.as_ref())
+    }
+
+    /// Matches the provided path against this pattern, recording how each part of it matched.
+    ///
+    /// On a successful match, the returned [`Explanation`] contains the part of the path
+    /// that each wildcard matched. Otherwise, it contains the furthest point that matching
+    /// reached and the part of the pattern that failed to match there.
+    ///
+    /// This is slower than [`Glob::matches`] and is meant for diagnostics.
+    ///
+    /// ```
+    /// use ec4rs_glob::{Element, Glob};
+    /// let glob = Glob::new("*.{rs,toml}");
+    /// let explanation = glob.explain("/src/lib.rs");
+    /// let captures = explanation.captures().unwrap();
+    /// assert_eq!(captures[0].element, Element::AnySeq);
+    /// assert_eq!(&explanation.path()[captures[0].span.clone()], "lib");
+    /// assert_eq!(&explanation.path()[captures[1].span.clone()], "rs");
+    ///
+    /// let glob = Glob::new("src/*.rs");
+    /// let explanation = glob.explain("/lib/main.rs");
+    /// let mismatch = explanation.mismatch().unwrap();
+    /// assert_eq!(mismatch.element, Some(Element::Literal("src")));
+    /// assert_eq!(&explanation.path()[..mismatch.position], "/lib");
+    /// ```
+    #[cfg(not(feature = "no_std"))]
+    #[must_use]
+    pub fn explain(&self, path: impl AsRef<std::path::Path>) -> Explanation<'_> {
+        explain::explain(Components::from_path(path.as_ref()), self)
+    }
+
+    /// As [`Glob::explain`], but for a `/`-separated path as in [`Glob::matches_str`].
+    #[must_use]
+    pub fn explain_str(&self, path: &str) -> Explanation<'_> {
+        explain::explain(Components::from_str(path), self)
+    }
+
+    /// Returns the parts of the pattern as it was parsed,
+    /// e.g. for translating it into a pattern for another glob engine.
+    ///
+    /// The parts are those of the canonical form of the pattern written by `Display`.
+    /// Patterns without a `/` do not start with a [`Part::Start`] or [`Part::Separator`],
+    /// and match files in any directory.
+    ///
+    /// ```
+    /// use ec4rs_glob::{Glob, Part};
+    /// let glob = Glob::new("/src/**/*.{rs,toml}");
+    /// let parts: Vec<_> = glob.parts().collect();
+    /// assert!(matches!(parts[0], Part::Start));
+    /// assert!(matches!(parts[1], Part::Literal("src")));
+    /// assert!(matches!(parts[3], Part::AnyPath));
+    /// if let Part::Alternation(options) = &parts[7] {
+    ///     assert_eq!(options.len(), 2);
+    /// } else {
+    ///     panic!("expected an alternation");
+    /// }
+    /// ```
+    pub fn parts(&self) -> Parts<'_> {
+        let mut parts = Parts::new(self);
+        // The leading separator of a pattern without slashes is implicit.
+        if let Some(Matcher::Sep) = self.0.first() {
+            parts.next();
+        }
+        parts
+    }
+
+    /// Returns `true` if every path that this pattern matches is also matched by `other`.
+    ///
+    /// This is decided without enumerating paths,
+    /// taking into account every kind of wildcard, including numeric ranges.
+    /// Paths are considered in normalized form, i.e. as non-empty components joined by `/`.
+    ///
+    /// The result does not account for the `graphemes` feature.
+    ///
+    /// ```
+    /// use ec4rs_glob::Glob;
+    /// assert!(Glob::new("src/*.rs").is_subset_of(&Glob::new("*.{rs,toml}")));
+    /// assert!(Glob::new("file{2..5}").is_subset_of(&Glob::new("file[0-9]")));
+    /// assert!(!Glob::new("**.rs").is_subset_of(&Glob::new("/*.rs")));
+    /// ```
+    #[must_use]
+    pub fn is_subset_of(&self, other: &Glob) -> bool {
+        analysis::is_subset(self, other)
+    }
+
+    /// Returns `true` if there is a path that both this pattern and `other` match.
+    ///
+    /// The same caveats as for [`Glob::is_subset_of`] apply.
+    ///
+    /// ```
+    /// use ec4rs_glob::Glob;
+    /// assert!(Glob::new("*.{c,h}").overlaps(&Glob::new("include/*")));
+    /// assert!(!Glob::new("{1..9}.txt").overlaps(&Glob::new("{10..20}.txt")));
+    /// ```
+    #[must_use]
+    pub fn overlaps(&self, other: &Glob) -> bool {
+        analysis::overlaps(self, other)
+    }
+
+    /// Returns an iterator over the files under `base`
+    /// whose paths relative to `base` match this pattern.
+    ///
+    /// The yielded paths are `base` joined with those relative paths.
+    /// Entries of each directory are visited in order of their names, depth-first.
+    ///
+    /// Directories that cannot contain matches are not read.
+    /// Leading path components of the pattern that are literal names are looked up directly,
+    /// and the number of `/`s in the pattern limits how deep the search goes
+    /// unless the pattern contains `**`.
+    /// Patterns that do not contain a `/` can match files at any depth.
+    ///
+    /// Symbolic links are not followed; links that match are yielded like files.
+    /// Errors from reading directories are yielded and the search continues past them.
+    ///
+    /// ```no_run
+    /// use ec4rs_glob::Glob;
+    /// for path in Glob::new("src/**/*.rs").walk("/path/to/project") {
+    ///     println!("{}", path?.display());
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(not(feature = "no_std"))]
+    pub fn walk(&self, base: impl AsRef<std::path::Path>) -> Walk<'_> {
+        Walk::new(self, base.as_ref())
     }
 
     /// Append one [`Matcher`] to `self`.
@@ -1,3 +1,7 @@
+use alloc::string::String;
+
+use super::path::Components;
+use super::stack::{SaveStack, Trace};
 use super::{Glob, Splitter};
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -7,21 +11,89 @@ pub enum Matcher {
     AnyChar,
     Sep,
     Suffix(String),
-    // TODO: Grapheme clusters?
     CharClass(super::FlatSet<char>, bool),
-    Range(isize, isize),
+    Range(super::number::NumRange),
     Any(super::FlatSet<super::Glob>),
 }
 
-fn try_match<'a, 'b>(
+/// Writes a character outside of a character class, escaping it if needed.
+fn fmt_escaped(c: char, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    if matches!(c, '\\' | '?' | '*' | '[' | '{' | '}' | ',') {
+        write!(f, "\\")?;
+    }
+    write!(f, "{}", c)
+}
+
+/// Writes a character inside of a character class, escaping it if needed.
+fn fmt_escaped_class(c: char, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    if matches!(c, '\\' | ']' | '-' | '!') {
+        write!(f, "\\")?;
+    }
+    write!(f, "{}", c)
+}
+
+impl core::fmt::Display for Matcher {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            // End only ever appears at the start of a pattern that contains a slash.
+            Matcher::End | Matcher::Sep => write!(f, "/"),
+            // `**` is parsed as AnySeq(true) followed by AnySeq(false).
+            Matcher::AnySeq(_) => write!(f, "*"),
+            Matcher::AnyChar => write!(f, "?"),
+            Matcher::Suffix(s) => s.chars().try_for_each(|c| fmt_escaped(c, f)),
+            Matcher::CharClass(cs, should_have) => {
+                write!(f, "[")?;
+                if !should_have {
+                    write!(f, "!")?;
+                }
+                let mut chars = cs.as_slice().iter().copied().peekable();
+                while let Some(first) = chars.next() {
+                    // Collapse runs of three or more consecutive characters into ranges.
+                    let mut last = first;
+                    let mut len = 1usize;
+                    while let Some(&next) = chars.peek() {
+                        if u32::from(last) + 1 != u32::from(next) {
+                            break;
+                        }
+                        last = next;
+                        len += 1;
+                        chars.next();
+                    }
+                    fmt_escaped_class(first, f)?;
+                    if len >= 3 {
+                        write!(f, "-")?;
+                    }
+                    if len >= 2 {
+                        fmt_escaped_class(last, f)?;
+                    }
+                }
+                write!(f, "]")
+            }
+            Matcher::Range(range) => range.fmt(f),
+            Matcher::Any(options) => {
+                write!(f, "{{")?;
+                for (idx, option) in options.as_slice().iter().enumerate() {
+                    if idx != 0 {
+                        write!(f, ",")?;
+                    }
+                    option.0.iter().try_for_each(|m| m.fmt(f))?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn try_match<'a, 'b, T: Trace<'b>>(
     splitter: Splitter<'a>,
     matcher: &'b Matcher,
-    state: &mut super::stack::SaveStack<'a, 'b>,
+    state: &mut SaveStack<'a, 'b, T>,
 ) -> Option<Splitter<'a>> {
-    match matcher {
+    let consumed = splitter.consumed();
+    let result = match matcher {
         Matcher::End => splitter.match_end(),
         Matcher::Sep => splitter.match_sep(),
-        Matcher::AnyChar => splitter.match_any(false),
+        Matcher::AnyChar => splitter.match_unit(),
         Matcher::AnySeq(sep) => {
             if let Some(splitter) = splitter.clone().match_any(*sep) {
                 state.add_rewind(splitter, matcher);
@@ -29,36 +101,102 @@ fn try_match<'a, 'b>(
             Some(splitter)
         }
         Matcher::Suffix(s) => splitter.match_suffix(s.as_str()),
-        Matcher::CharClass(cs, should_have) => {
-            let (splitter, c) = splitter.next_char()?;
-            if cs.contains(c) != *should_have {
-                return None;
+        Matcher::CharClass(cs, should_have) => splitter.next_unit().and_then(|(splitter, c)| {
+            // A grapheme cluster of several characters is in no class.
+            let in_class = c.map_or(false, |c| cs.contains(c));
+            // Negated classes never match the path separator either.
+            (c != Some('/') && in_class == *should_have).then(|| splitter)
+        }),
+        Matcher::Range(range) => {
+            let mut matches = splitter.match_number(range).into_iter();
+            let first = matches.next();
+            // Shorter numbers are tried if the rest of the pattern fails to match.
+            for splitter in matches.rev() {
+                state.add_resume(splitter, matcher);
             }
-            Some(splitter)
+            first
         }
-        Matcher::Range(lower, upper) => splitter.match_number(*lower, *upper),
         Matcher::Any(options) => {
-            state.add_alts(splitter.clone(), options.as_slice());
-            Some(splitter)
+            // The step for the chosen option is traced by the SaveStack.
+            state.add_alts(splitter.clone(), matcher, options.as_slice());
+            return Some(splitter);
         }
+    };
+    if let Some(splitter) = &result {
+        state.trace().step(matcher, None, splitter.consumed());
+    } else {
+        state.trace().fail(matcher, consumed);
     }
+    result
 }
 
+/// The error returned by [`Glob::try_matches`][crate::Glob::try_matches]
+/// when matching gives up after too many steps.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LimitError {
+    limit: usize,
+}
+
+impl LimitError {
+    /// Returns the number of steps that matching was limited to.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl core::fmt::Display for LimitError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "gave up matching after {} steps", self.limit)
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for LimitError {}
+
 #[must_use]
-pub fn matches<'a>(path: &'a std::path::Path, glob: &Glob) -> Option<Splitter<'a>> {
-    let mut splitter = super::Splitter::new(path)?;
-    let mut state = super::stack::SaveStack::new(&splitter, glob);
+pub fn matches<'a>(path: Components<'a>, glob: &Glob) -> Option<Splitter<'a>> {
+    let splitter = super::Splitter::new(path)?;
+    run(splitter, glob, (), usize::MAX).unwrap_or(None)
+}
+
+/// As [`matches`], but gives up after matching `limit` elements of the pattern.
+pub fn matches_limited<'a>(
+    path: Components<'a>,
+    glob: &Glob,
+    limit: usize,
+) -> Result<Option<Splitter<'a>>, LimitError> {
+    match super::Splitter::new(path) {
+        Some(splitter) => run(splitter, glob, (), limit),
+        None => Ok(None),
+    }
+}
+
+/// Matches `glob` against the path in `splitter`, reporting each step to `trace`.
+///
+/// Fails if more than `limit` elements of the pattern are tried.
+pub fn run<'a, 'b, T: Trace<'b>>(
+    mut splitter: Splitter<'a>,
+    glob: &'b Glob,
+    trace: T,
+    limit: usize,
+) -> Result<Option<Splitter<'a>>, LimitError> {
+    let mut state = SaveStack::new(&splitter, glob, trace);
+    let mut steps = 0usize;
     loop {
         if let Some(matcher) = state.globs().next() {
+            if steps == limit {
+                return Err(LimitError { limit });
+            }
+            steps += 1;
             if let Some(splitter_new) = try_match(splitter, matcher, &mut state) {
                 splitter = splitter_new;
             } else if let Some(splitter_new) = state.restore() {
                 splitter = splitter_new;
             } else {
-                return None;
+                return Ok(None);
             }
         } else {
-            return Some(splitter);
+            return Ok(Some(splitter));
         }
     }
 }
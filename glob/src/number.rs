@@ -0,0 +1,174 @@
+use alloc::string::String;
+use core::cmp::Ordering;
+
+/// Compares two integers given as signs and ASCII digits without leading zeros.
+fn compare(negative_a: bool, a: &[u8], negative_b: bool, b: &[u8]) -> Ordering {
+    let magnitude = || a.len().cmp(&b.len()).then_with(|| a.cmp(b));
+    match (negative_a, negative_b) {
+        (false, false) => magnitude(),
+        (true, true) => magnitude().reverse(),
+        (false, true) => Ordering::Greater,
+        (true, false) => Ordering::Less,
+    }
+}
+
+/// Strips leading zeros from a string of ASCII digits, leaving at least one digit.
+fn strip_zeros(digits: &[u8]) -> &[u8] {
+    let zeros = digits.iter().take_while(|&&b| b == b'0').count();
+    &digits[zeros.min(digits.len().saturating_sub(1))..]
+}
+
+/// An integer of any size.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Integer(String);
+
+impl Integer {
+    /// Parses an optionally-negative string of ASCII digits.
+    ///
+    /// Also returns the number of digits and whether there were any leading zeros.
+    fn parse(text: &str) -> Option<(Integer, usize, bool)> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let stripped = strip_zeros(digits.as_bytes());
+        let mut canonical = String::with_capacity(stripped.len() + 1);
+        if negative && stripped != b"0" {
+            canonical.push('-');
+        }
+        canonical.extend(stripped.iter().map(|&b| b as char));
+        let padded = stripped.len() < digits.len();
+        Some((Integer(canonical), digits.len(), padded))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0.starts_with('-')
+    }
+
+    /// Returns the digits of the absolute value.
+    pub fn digits(&self) -> &str {
+        self.0.trim_start_matches('-')
+    }
+}
+
+impl PartialOrd for Integer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Integer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare(
+            self.is_negative(),
+            self.digits().as_bytes(),
+            other.is_negative(),
+            other.digits().as_bytes(),
+        )
+    }
+}
+
+/// An inclusive range of integers, as in `{-5..10}` or `{00..99}`.
+///
+/// Numbers in the range are written in decimal with a `-` for negative numbers.
+/// If either bound is written with leading zeros, numbers must be zero-padded to
+/// the number of digits in the longer bound, e.g. `{00..99}` matches `07` but not `7`.
+/// Otherwise, numbers must not have leading zeros.
+/// In both cases, zero is never written with a `-`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct NumRange {
+    lower: Integer,
+    upper: Integer,
+    /// The number of digits in every number if the range is zero-padded, otherwise `0`.
+    width: usize,
+}
+
+impl NumRange {
+    /// Parses a range from its two bounds, which may be in either order.
+    pub fn new(a: &str, b: &str) -> Option<NumRange> {
+        let (a, width_a, padded_a) = Integer::parse(a)?;
+        let (b, width_b, padded_b) = Integer::parse(b)?;
+        let width = if padded_a || padded_b {
+            width_a.max(width_b)
+        } else {
+            0
+        };
+        // Reading the spec strictly,
+        // a compliant implementation must handle cases where
+        // the left integer is greater than the right integer.
+        let (lower, upper) = if a <= b { (a, b) } else { (b, a) };
+        Some(NumRange {
+            lower,
+            upper,
+            width,
+        })
+    }
+
+    pub fn lower(&self) -> &Integer {
+        &self.lower
+    }
+
+    pub fn upper(&self) -> &Integer {
+        &self.upper
+    }
+
+    /// Returns the number of digits in every number if the range is zero-padded.
+    pub fn width(&self) -> Option<usize> {
+        (self.width != 0).then(|| self.width)
+    }
+
+    /// Returns the most digits that a number in this range can be written with.
+    pub fn max_digits(&self) -> usize {
+        self.width()
+            .unwrap_or_else(|| self.lower.digits().len().max(self.upper.digits().len()))
+    }
+
+    /// Returns `true` if `digits`, preceded by a `-` if `negative` is `true`,
+    /// is how a number in this range is written.
+    pub fn contains(&self, negative: bool, digits: &[u8]) -> bool {
+        let stripped = strip_zeros(digits);
+        let well_formed = match self.width() {
+            Some(width) => digits.len() == width,
+            None => stripped.len() == digits.len(),
+        };
+        well_formed
+            && !(negative && stripped == b"0")
+            && compare(
+                negative,
+                stripped,
+                self.lower.is_negative(),
+                self.lower.digits().as_bytes(),
+            ) != Ordering::Less
+            && compare(
+                negative,
+                stripped,
+                self.upper.is_negative(),
+                self.upper.digits().as_bytes(),
+            ) != Ordering::Greater
+    }
+
+    /// Writes a bound, zero-padding it if necessary.
+    fn fmt_bound(&self, bound: &Integer, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if bound.is_negative() {
+            write!(f, "-")?;
+        }
+        write!(f, "{:0>1$}", bound.digits(), self.width)
+    }
+}
+
+impl core::fmt::Display for NumRange {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{{")?;
+        self.fmt_bound(&self.lower, f)?;
+        write!(f, "..")?;
+        self.fmt_bound(&self.upper, f)?;
+        write!(f, "}}")
+    }
+}
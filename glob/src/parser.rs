@@ -5,4 +5,112 @@ mod numrange;
 
 pub use main::parse;
 
-type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// The kinds of syntax errors that can occur in a glob pattern.
+///
+/// The permissive parser used by [`Glob::new`][crate::Glob::new]
+/// treats the offending text as if it were escaped.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum SyntaxErrorKind {
+    /// A `\` at the end of the pattern or right before a `/`.
+    DanglingEscape,
+    /// A `[` without a matching `]` before the end of the path segment.
+    UnclosedCharClass,
+    /// A `[]`.
+    EmptyCharClass,
+    /// A `{` without a matching `}`.
+    UnclosedAlternation,
+    /// A `}` without a matching `{`.
+    UnmatchedBrace,
+    /// A `{}`.
+    EmptyAlternation,
+    /// A pair of braces with only one option and no commas, e.g. `{foo}`.
+    SingleAlternative,
+}
+
+impl core::fmt::Display for SyntaxErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            SyntaxErrorKind::DanglingEscape => "dangling escape",
+            SyntaxErrorKind::UnclosedCharClass => "unclosed character class",
+            SyntaxErrorKind::EmptyCharClass => "empty character class",
+            SyntaxErrorKind::UnclosedAlternation => "unclosed alternation",
+            SyntaxErrorKind::UnmatchedBrace => "unmatched closing brace",
+            SyntaxErrorKind::EmptyAlternation => "empty alternation",
+            SyntaxErrorKind::SingleAlternative => "alternation with only one option",
+        })
+    }
+}
+
+/// A syntax error in a glob pattern.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SyntaxError {
+    /// What the error is.
+    pub kind: SyntaxErrorKind,
+    /// The byte range of the pattern containing the error.
+    pub span: Range<usize>,
+}
+
+impl core::fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} at {}..{}", self.kind, self.span.start, self.span.end)
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for SyntaxError {}
+
+/// Collection of the syntax errors encountered while parsing.
+#[derive(Default)]
+pub struct Errors(Vec<SyntaxError>);
+
+impl Errors {
+    pub fn add(&mut self, kind: SyntaxErrorKind, span: Range<usize>) {
+        self.0.push(SyntaxError { kind, span })
+    }
+    /// Returns the errors in the order they appear in the pattern.
+    pub fn into_sorted(mut self) -> Vec<SyntaxError> {
+        self.0
+            .sort_by_key(|error| (error.span.start, error.span.end));
+        self.0
+    }
+}
+
+/// Iterator over the characters of one path segment of a pattern
+/// that keeps track of byte offsets into the pattern.
+#[derive(Clone)]
+pub struct Chars<'a> {
+    iter: core::str::Chars<'a>,
+    end: usize,
+}
+
+impl<'a> Chars<'a> {
+    /// Creates a new `Chars` for a segment that ends at byte offset `end` of the pattern.
+    pub fn new(segment: &'a str, end: usize) -> Self {
+        Chars {
+            iter: segment.chars(),
+            end,
+        }
+    }
+    /// Returns the byte offset of the next character.
+    pub fn offset(&self) -> usize {
+        self.end - self.iter.as_str().len()
+    }
+    /// Returns the byte offset of the end of the segment.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+    pub fn peek(&self) -> Option<char> {
+        self.iter.clone().next()
+    }
+}
+
+impl<'a> Iterator for Chars<'a> {
+    type Item = char;
+    fn next(&mut self) -> Option<char> {
+        self.iter.next()
+    }
+}
@@ -1,3 +1,7 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{Errors, SyntaxErrorKind};
 use crate::{Glob, Matcher};
 
 pub struct AltStack(Vec<AltBuilder>);
@@ -11,8 +15,9 @@ impl AltStack {
         self.0.is_empty()
     }
 
-    pub fn push(&mut self, glob: Glob) {
-        self.0.push(AltBuilder::new(glob));
+    /// Pushes a new builder for an alternation whose `{` is at byte offset `start`.
+    pub fn push(&mut self, glob: Glob, start: usize) {
+        self.0.push(AltBuilder::new(glob, start));
     }
 
     /// Adds a glob to the top builder of the stack.
@@ -28,19 +33,22 @@ impl AltStack {
         }
     }
 
-    pub fn join_and_pop(&mut self, glob: Glob) -> (Glob, bool) {
+    /// Pops the top builder, treating its alternation as unclosed at byte offset `end`.
+    pub fn join_and_pop(&mut self, glob: Glob, end: usize, errors: &mut Errors) -> (Glob, bool) {
         if let Some(mut builder) = self.0.pop() {
             builder.add(glob);
+            errors.add(SyntaxErrorKind::UnclosedAlternation, builder.start..end);
             (builder.join(), self.is_empty())
         } else {
             (glob, true)
         }
     }
 
-    pub fn add_alt_and_pop(&mut self, glob: Glob) -> (Glob, bool) {
+    /// Pops the top builder, closing its alternation with the `}` at byte offset `end`.
+    pub fn add_alt_and_pop(&mut self, glob: Glob, end: usize, errors: &mut Errors) -> (Glob, bool) {
         if let Some(mut builder) = self.0.pop() {
             builder.add(glob);
-            (builder.build(), false)
+            (builder.build(end, errors), false)
         } else {
             (glob, true)
         }
@@ -50,26 +58,38 @@ impl AltStack {
 pub struct AltBuilder {
     glob: Glob,
     options: Vec<Glob>,
+    /// Byte offset of the opening brace.
+    start: usize,
 }
 
 impl AltBuilder {
-    pub const fn new(glob: Glob) -> AltBuilder {
+    pub const fn new(glob: Glob, start: usize) -> AltBuilder {
         AltBuilder {
             glob,
             options: vec![],
+            start,
         }
     }
     pub fn add(&mut self, glob: Glob) {
         self.options.push(glob);
     }
-    pub fn build(mut self) -> Glob {
+    /// Builds the alternation, which was closed by the `}` at byte offset `end`.
+    pub fn build(mut self, end: usize, errors: &mut Errors) -> Glob {
+        let span = self.start..end + 1;
         match self.options.len() {
             0 => {
+                errors.add(SyntaxErrorKind::EmptyAlternation, span);
                 self.glob.append_escaped('{');
                 self.glob.append_escaped('}');
                 self.glob
             }
             1 => {
+                let kind = if self.options[0].0.is_empty() {
+                    SyntaxErrorKind::EmptyAlternation
+                } else {
+                    SyntaxErrorKind::SingleAlternative
+                };
+                errors.add(kind, span);
                 self.glob.append_escaped('{');
                 for matcher in self.options.pop().unwrap().0 {
                     self.glob.push(matcher);
@@ -81,7 +101,12 @@ impl AltBuilder {
                 self.options
                     .sort_by(|a, b| (!a.0.is_empty()).cmp(&!b.0.is_empty()));
                 self.options.dedup();
-                self.glob.push(Matcher::Any(self.options.into()));
+                if self.options.len() == 1 {
+                    // Every option was the same, e.g. `{a,a}`.
+                    self.glob.append_glob(self.options.pop().unwrap());
+                } else {
+                    self.glob.push(Matcher::Any(self.options.into()));
+                }
                 self.glob
             }
         }
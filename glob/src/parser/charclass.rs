@@ -1,6 +1,6 @@
-use std::collections::BTreeSet;
+use alloc::collections::BTreeSet;
 
-use super::Chars;
+use super::{Chars, Errors, SyntaxErrorKind};
 use crate::{Glob, Matcher};
 
 #[inline]
@@ -39,10 +39,17 @@ fn grow_char_class(chars: &mut Chars<'_>, charclass: &mut BTreeSet<char>) -> Opt
     }
 }
 
-pub fn parse(mut glob: Glob, mut chars: Chars<'_>) -> (Glob, Chars<'_>) {
+/// Parses a character class whose `[` is at byte offset `start`.
+pub fn parse<'a>(
+    mut glob: Glob,
+    mut chars: Chars<'a>,
+    start: usize,
+    errors: &mut Errors,
+) -> (Glob, Chars<'a>) {
     let invert = if let Some(c) = chars.peek() {
-        *c == '!'
+        c == '!'
     } else {
+        errors.add(SyntaxErrorKind::UnclosedCharClass, start..chars.end());
         glob.append_escaped('[');
         return (glob, chars);
     };
@@ -59,16 +66,18 @@ pub fn parse(mut glob: Glob, mut chars: Chars<'_>) -> (Glob, Chars<'_>) {
                 if invert {
                     glob.push(Matcher::AnyChar);
                 } else {
+                    errors.add(SyntaxErrorKind::EmptyCharClass, start..chars.offset());
                     glob.append_escaped('[');
                     glob.append_escaped(']');
                 }
             }
             // Don't use BTreeSet::first here (stable: 1.66).
-            1 => glob.append_escaped(*charclass.iter().next().unwrap()),
+            1 if !invert => glob.append_escaped(*charclass.iter().next().unwrap()),
             _ => glob.push(Matcher::CharClass(charclass.into(), !invert)),
         }
         (glob, chars)
     } else {
+        errors.add(SyntaxErrorKind::UnclosedCharClass, start..chars.end());
         glob.append_escaped('[');
         (glob, restore)
     }
@@ -1,39 +1,40 @@
 use super::alt::AltStack;
+use super::{Chars, Errors, SyntaxErrorKind};
 use crate::{Glob, Matcher};
 
-pub fn parse(glob: &str) -> Glob {
-    let mut retval = Glob(vec![]);
+pub fn parse(glob: &str, errors: &mut Errors) -> Glob {
+    let mut retval = Glob::empty();
     let mut stack = AltStack::new();
+    let mut segment_start = 0usize;
     for segment in glob.split('/') {
         retval.append_escaped('/');
-        let mut chars = segment.chars().peekable();
+        let mut chars = Chars::new(segment, segment_start + segment.len());
+        segment_start += segment.len() + 1;
         while let Some(c) = chars.next() {
+            let offset = chars.offset() - c.len_utf8();
             match c {
                 '\\' => {
                     if let Some(escaped) = chars.next() {
                         retval.append_escaped(escaped);
+                    } else {
+                        errors.add(SyntaxErrorKind::DanglingEscape, offset..offset + 1);
                     }
                 }
                 '?' => retval.push(Matcher::AnyChar),
                 '*' => retval.push(Matcher::AnySeq(matches!(chars.peek(), Some('*')))),
                 '[' => {
-                    let (retval_n, chars_n) = super::charclass::parse(retval, chars);
+                    let (retval_n, chars_n) =
+                        super::charclass::parse(retval, chars, offset, errors);
                     retval = retval_n;
                     chars = chars_n;
                 }
                 '{' => {
-                    if let Some((a, b, chars_new)) = super::numrange::parse(chars.clone()) {
+                    if let Some((range, chars_new)) = super::numrange::parse(chars.clone()) {
                         chars = chars_new;
-                        retval.push(Matcher::Range(
-                            // Reading the spec strictly,
-                            // a compliant implementation must handle cases where
-                            // the left integer is greater than the right integer.
-                            std::cmp::min(a, b),
-                            std::cmp::max(a, b),
-                        ));
+                        retval.push(Matcher::Range(range));
                     } else {
-                        stack.push(retval);
-                        retval = Glob(vec![]);
+                        stack.push(retval, offset);
+                        retval = Glob::empty();
                     }
                 }
                 ',' => {
@@ -41,13 +42,14 @@ pub fn parse(glob: &str) -> Glob {
                         retval = rejected;
                         retval.append_escaped(',');
                     } else {
-                        retval = Glob(vec![]);
+                        retval = Glob::empty();
                     }
                 }
                 '}' => {
-                    let (retval_n, add_brace) = stack.add_alt_and_pop(retval);
+                    let (retval_n, add_brace) = stack.add_alt_and_pop(retval, offset, errors);
                     retval = retval_n;
                     if add_brace {
+                        errors.add(SyntaxErrorKind::UnmatchedBrace, offset..offset + 1);
                         retval.append_escaped('}');
                     }
                 }
@@ -56,7 +58,7 @@ pub fn parse(glob: &str) -> Glob {
         }
     }
     loop {
-        let (retval_n, is_empty) = stack.join_and_pop(retval);
+        let (retval_n, is_empty) = stack.join_and_pop(retval, glob.len(), errors);
         retval = retval_n;
         if is_empty {
             break;
@@ -68,5 +70,6 @@ pub fn parse(glob: &str) -> Glob {
     if let Some(Matcher::Sep) = retval.0.last() {
         retval.push(Matcher::AnySeq(false));
     }
+    retval.1 = crate::dfa::Cache::new();
     retval
 }
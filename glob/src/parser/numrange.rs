@@ -1,10 +1,13 @@
+use alloc::string::String;
+
 use super::Chars;
+use crate::number::NumRange;
 
 fn parse_int(chars: &mut Chars<'_>, breaker: char) -> Option<String> {
     let mut num = String::with_capacity(2);
-    num.push(chars.next().filter(|c| c.is_numeric() || *c == '-')?);
+    num.push(chars.next().filter(|c| c.is_ascii_digit() || *c == '-')?);
     for c in chars {
-        if c.is_numeric() {
+        if c.is_ascii_digit() {
             num.push(c)
         } else if c == breaker {
             return Some(num);
@@ -15,11 +18,11 @@ fn parse_int(chars: &mut Chars<'_>, breaker: char) -> Option<String> {
     None
 }
 
-pub fn parse(mut chars: Chars<'_>) -> Option<(isize, isize, Chars<'_>)> {
+pub fn parse(mut chars: Chars<'_>) -> Option<(NumRange, Chars<'_>)> {
     let num_a = parse_int(&mut chars, '.')?;
     if !matches!(chars.next(), Some('.')) {
         return None;
     }
     let num_b: String = parse_int(&mut chars, '}')?;
-    Some((num_a.parse().ok()?, num_b.parse().ok()?, chars))
+    Some((NumRange::new(&num_a, &num_b)?, chars))
 }
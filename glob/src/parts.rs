@@ -0,0 +1,98 @@
+use super::{Glob, Matcher};
+
+/// One part of a parsed glob pattern, as returned by [`Glob::parts`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Part<'a> {
+    /// The start of the path, which patterns containing a `/` are anchored to.
+    Start,
+    /// A path separator, `/`.
+    Separator,
+    /// Literal text.
+    Literal(&'a str),
+    /// `?`, any one character or extended grapheme cluster.
+    AnyChar,
+    /// `*`, any sequence of characters except `/`.
+    AnySeq,
+    /// `**`, any sequence of characters.
+    AnyPath,
+    /// A character class, e.g. `[a-z]` or `[!a-z]`.
+    /// Contains the characters in the class in ascending order and whether it is negated.
+    ///
+    /// Negated classes never match a `/`.
+    CharClass(&'a [char], bool),
+    /// A numeric range, e.g. `{1..42}`.
+    /// Contains the inclusive lower and upper bounds in decimal, without any zero-padding,
+    /// and the number of digits that numbers must be zero-padded to, if any.
+    Range(&'a str, &'a str, Option<usize>),
+    /// An alternation, e.g. `{a,b}`. Contains the parts of each alternative.
+    Alternation(Alternatives<'a>),
+}
+
+/// An iterator over the parts of a glob pattern. See [`Glob::parts`].
+#[derive(Clone, Debug)]
+pub struct Parts<'a>(&'a [Matcher]);
+
+impl<'a> Parts<'a> {
+    pub(crate) fn new(glob: &'a Glob) -> Self {
+        Parts(&glob.0)
+    }
+}
+
+impl<'a> Iterator for Parts<'a> {
+    type Item = Part<'a>;
+
+    fn next(&mut self) -> Option<Part<'a>> {
+        let (first, rest) = self.0.split_first()?;
+        self.0 = rest;
+        Some(match first {
+            Matcher::End => Part::Start,
+            Matcher::Sep => Part::Separator,
+            Matcher::Suffix(s) => Part::Literal(s.as_str()),
+            Matcher::AnyChar => Part::AnyChar,
+            Matcher::AnySeq(sep) => {
+                // `**` is parsed as AnySeq(true) followed by AnySeq(false).
+                let run = rest
+                    .iter()
+                    .take_while(|m| matches!(m, Matcher::AnySeq(_)))
+                    .count();
+                let any_path = *sep || rest[..run].contains(&Matcher::AnySeq(true));
+                self.0 = &rest[run..];
+                if any_path {
+                    Part::AnyPath
+                } else {
+                    Part::AnySeq
+                }
+            }
+            Matcher::CharClass(cs, should_have) => Part::CharClass(cs.as_slice(), !should_have),
+            Matcher::Range(range) => Part::Range(
+                range.lower().as_str(),
+                range.upper().as_str(),
+                range.width(),
+            ),
+            Matcher::Any(options) => Part::Alternation(Alternatives(options.as_slice().iter())),
+        })
+    }
+}
+
+impl core::iter::FusedIterator for Parts<'_> {}
+
+/// An iterator over the parts of each alternative of an alternation.
+#[derive(Clone, Debug)]
+pub struct Alternatives<'a>(core::slice::Iter<'a, Glob>);
+
+impl<'a> Iterator for Alternatives<'a> {
+    type Item = Parts<'a>;
+
+    fn next(&mut self) -> Option<Parts<'a>> {
+        self.0.next().map(Parts::new)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Alternatives<'_> {}
+
+impl core::iter::FusedIterator for Alternatives<'_> {}
@@ -0,0 +1,130 @@
+//! Paths as sequences of components,
+//! either from a [`std::path::Path`] or from a `/`-separated string.
+
+/// One component of a path.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Component<'a> {
+    /// The root directory, a prefix, or a leading `.`. These only occur at the start of a path.
+    Start,
+    /// The name of a directory or file, including `..`.
+    Name(&'a [u8]),
+    /// A name that cannot be viewed as bytes on this platform.
+    #[cfg_attr(feature = "no_std", allow(dead_code))]
+    Invalid,
+}
+
+// Problem.
+// OsStr cannot be cast to &[u8] on Windows.
+// On Unixes and WASM it's fine.
+
+#[cfg(all(not(feature = "no_std"), target_family = "unix"))]
+mod cnv {
+    use std::ffi::OsStr;
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn to_bytes(s: &OsStr) -> Option<&[u8]> {
+        use std::os::unix::ffi::OsStrExt;
+        Some(s.as_bytes())
+    }
+}
+
+#[cfg(all(not(feature = "no_std"), target_os = "wasi"))]
+mod cnv {
+    use std::ffi::OsStr;
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn to_bytes(s: &OsStr) -> Option<&[u8]> {
+        use std::os::wasi::ffi::OsStrExt;
+        Some(s.as_bytes())
+    }
+}
+
+#[cfg(all(
+    not(feature = "no_std"),
+    not(target_family = "unix"),
+    not(target_os = "wasi")
+))]
+mod cnv {
+    use std::ffi::OsStr;
+    pub fn to_bytes(s: &OsStr) -> Option<&[u8]> {
+        s.to_str().map(|s| s.as_ref())
+    }
+}
+
+/// An iterator over the [`Component`]s of a path.
+#[derive(Clone)]
+pub enum Components<'a> {
+    #[cfg(not(feature = "no_std"))]
+    Path(std::path::Components<'a>),
+    /// A `/`-separated path, split the same way [`std::path::Path::components`] would.
+    Str {
+        /// Whether [`Component::Start`] has yet to be yielded.
+        start: bool,
+        names: core::str::Split<'a, char>,
+    },
+}
+
+impl<'a> Components<'a> {
+    #[cfg(not(feature = "no_std"))]
+    pub fn from_path(path: &'a std::path::Path) -> Self {
+        Components::Path(path.components())
+    }
+
+    pub fn from_str(path: &'a str) -> Self {
+        Components::Str {
+            start: path.starts_with('/') || path == "." || path.starts_with("./"),
+            names: path.split('/'),
+        }
+    }
+}
+
+fn is_name(name: &&str) -> bool {
+    !matches!(*name, "" | ".")
+}
+
+#[cfg(not(feature = "no_std"))]
+fn convert(component: std::path::Component<'_>) -> Component<'_> {
+    use std::path::Component as C;
+    match component {
+        C::Normal(name) => cnv::to_bytes(name).map_or(Component::Invalid, Component::Name),
+        C::ParentDir => Component::Name(b".."),
+        C::CurDir | C::RootDir | C::Prefix(_) => Component::Start,
+    }
+}
+
+impl<'a> Iterator for Components<'a> {
+    type Item = Component<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            #[cfg(not(feature = "no_std"))]
+            Components::Path(iter) => iter.next().map(convert),
+            Components::Str { start, names } => {
+                if *start {
+                    *start = false;
+                    return Some(Component::Start);
+                }
+                names
+                    .find(is_name)
+                    .map(|name| Component::Name(name.as_bytes()))
+            }
+        }
+    }
+}
+
+impl<'a> DoubleEndedIterator for Components<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            #[cfg(not(feature = "no_std"))]
+            Components::Path(iter) => iter.next_back().map(convert),
+            Components::Str { start, names } => {
+                if let Some(name) = names.rfind(is_name) {
+                    Some(Component::Name(name.as_bytes()))
+                } else if *start {
+                    *start = false;
+                    Some(Component::Start)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
@@ -1,57 +1,41 @@
-// Problem.
-// OsStr cannot be cast to &[u8] on Windows.
-// On Unixes and WASM it's fine.
-
-#[cfg(target_family = "unix")]
-mod cnv {
-    use std::ffi::OsStr;
-    #[allow(clippy::unnecessary_wraps)]
-    pub fn to_bytes(s: &OsStr) -> Option<&[u8]> {
-        use std::os::unix::ffi::OsStrExt;
-        Some(s.as_bytes())
-    }
-}
-
-#[cfg(target_os = "wasi")]
-mod cnv {
-    use std::ffi::OsStr;
-    #[allow(clippy::unnecessary_wraps)]
-    pub fn to_bytes(s: &OsStr) -> Option<&[u8]> {
-        use std::os::wasi::ffi::OsStrExt;
-        Some(s.as_bytes())
-    }
-}
+use alloc::vec::Vec;
 
-#[cfg(all(not(target_family = "unix"), not(target_os = "wasi")))]
-mod cnv {
-    use std::ffi::OsStr;
-    pub fn to_bytes(s: &OsStr) -> Option<&[u8]> {
-        s.to_str().map(|s| s.as_ref())
-    }
-}
+use crate::number::NumRange;
+use crate::path::{Component, Components};
 
 #[derive(Clone)]
 pub struct Splitter<'a> {
-    iter: std::path::Components<'a>,
+    iter: Components<'a>,
     part: &'a [u8],
     matched_sep: bool,
+    /// Number of bytes consumed from the end of the path, counting separators as one byte.
+    consumed: usize,
 }
 
 impl<'a> Splitter<'a> {
-    pub fn new(path: &'a std::path::Path) -> Option<Self> {
-        Splitter {
-            iter: path.components(),
+    pub fn new(iter: Components<'a>) -> Option<Self> {
+        let mut splitter = Splitter {
+            iter,
             part: "".as_bytes(),
             matched_sep: false,
+            consumed: 0,
         }
-        .next()
+        .next()?;
+        splitter.consumed = 0;
+        Some(splitter)
+    }
+
+    /// Returns the number of bytes consumed from the end of the path so far.
+    ///
+    /// A separator that has been matched but not yet stepped over counts as consumed.
+    pub fn consumed(&self) -> usize {
+        self.consumed + usize::from(self.matched_sep)
     }
 
     pub fn match_end(mut self) -> Option<Self> {
-        use std::path::Component as C;
         if self.part.is_empty() {
             let next = self.iter.next_back();
-            if matches!(next, None | Some(C::CurDir | C::RootDir | C::Prefix(_))) {
+            if matches!(next, None | Some(Component::Start)) {
                 return Some(self);
             }
         }
@@ -59,19 +43,40 @@ impl<'a> Splitter<'a> {
     }
 
     pub fn next(mut self) -> Option<Self> {
-        use std::path::Component;
         self.part = match self.iter.next_back()? {
-            Component::Normal(p) => cnv::to_bytes(p)?,
-            Component::ParentDir => "..".as_bytes(),
-            _ => "".as_bytes(),
+            Component::Name(p) => p,
+            Component::Invalid => return None,
+            Component::Start => "".as_bytes(),
         };
+        self.matched_sep = false;
+        self.consumed += 1;
         Some(self)
     }
 
+    /// Steps over a separator that has been matched but not yet stepped over.
+    fn resume(self) -> Option<Self> {
+        if self.part.is_empty() && self.matched_sep {
+            self.next()
+        } else {
+            Some(self)
+        }
+    }
+
+    /// Consumes the end of the current part, keeping the first `idx` bytes.
+    fn truncate(mut self, idx: usize) -> Self {
+        self.consumed += self.part.len() - idx;
+        self.part = self.part.split_at(idx).0;
+        self
+    }
+
     pub fn match_any(mut self, path_sep: bool) -> Option<Self> {
+        self = self.resume()?;
         if !self.part.is_empty() {
-            self.part = self.part.split_last().unwrap().1;
-            Some(self)
+            // Step back by one character, or by one byte if the part isn't valid UTF-8.
+            let idx = self
+                .find_next_char()
+                .map_or(self.part.len() - 1, |(idx, _)| idx);
+            Some(self.truncate(idx))
         } else if path_sep {
             self.match_sep()?.next()
         } else {
@@ -80,14 +85,48 @@ impl<'a> Splitter<'a> {
     }
 
     pub fn next_char(mut self) -> Option<(Self, char)> {
+        self = self.resume()?;
         if let Some((idx, c)) = self.find_next_char() {
-            self.part = self.part.split_at(idx).0;
-            Some((self, c))
+            Some((self.truncate(idx), c))
         } else {
             Some((self.next()?, '/'))
         }
     }
 
+    /// Matches one character,
+    /// or one extended grapheme cluster if the `graphemes` feature is enabled.
+    pub fn match_unit(mut self) -> Option<Self> {
+        self = self.resume()?;
+        #[cfg(feature = "graphemes")]
+        if let Some((idx, _)) = self.find_next_grapheme() {
+            return Some(self.truncate(idx));
+        }
+        self.match_any(false)
+    }
+
+    /// As [`Splitter::next_char`], but steps back by one extended grapheme cluster
+    /// if the `graphemes` feature is enabled.
+    ///
+    /// Returns the cluster's character if it is only one character, otherwise `None`.
+    pub fn next_unit(mut self) -> Option<(Self, Option<char>)> {
+        self = self.resume()?;
+        #[cfg(feature = "graphemes")]
+        if let Some((idx, c)) = self.find_next_grapheme() {
+            return Some((self.truncate(idx), c));
+        }
+        self.next_char().map(|(this, c)| (this, Some(c)))
+    }
+
+    #[cfg(feature = "graphemes")]
+    fn find_next_grapheme(&self) -> Option<(usize, Option<char>)> {
+        use unicode_segmentation::UnicodeSegmentation;
+        let part = core::str::from_utf8(self.part).ok()?;
+        let grapheme = part.graphemes(true).next_back()?;
+        let mut chars = grapheme.chars();
+        let c = chars.next().filter(|_| chars.next().is_none());
+        Some((part.len() - grapheme.len(), c))
+    }
+
     fn find_next_char(&self) -> Option<(usize, char)> {
         let mut idx = self.part.len().checked_sub(1)?;
         let mut byte = self.part[idx];
@@ -96,7 +135,7 @@ impl<'a> Splitter<'a> {
             byte = self.part[idx];
         }
         // TODO: Do the UTF-8 character decode here ourselves.
-        let c = std::str::from_utf8(&self.part[idx..])
+        let c = core::str::from_utf8(&self.part[idx..])
             .ok()?
             .chars()
             .next_back()?;
@@ -112,11 +151,9 @@ impl<'a> Splitter<'a> {
     }
 
     pub fn match_suffix(mut self, suffix: &str) -> Option<Self> {
-        if self.part.is_empty() && self.matched_sep {
-            self.matched_sep = false;
-            self = self.next()?;
-        }
+        self = self.resume()?;
         if let Some(rest) = self.part.strip_suffix(suffix.as_bytes()) {
+            self.consumed += suffix.len();
             self.part = rest;
             Some(self)
         } else {
@@ -124,28 +161,26 @@ impl<'a> Splitter<'a> {
         }
     }
 
-    pub fn match_number(mut self, lower: isize, upper: isize) -> Option<Self> {
-        let mut q = std::collections::VecDeque::<char>::new();
-        let mut allow_zero: bool = true;
-        let mut last_ok = self.clone();
-        while let Some((next_ok, c)) = self.next_char() {
-            if c.is_numeric() && (c != '0' || allow_zero) {
-                last_ok = next_ok.clone();
-                allow_zero = c == '0';
-                q.push_front(c);
-            } else if c == '-' {
-                last_ok = next_ok.clone();
-                q.push_front('-');
-                break;
-            } else {
-                break;
+    /// Returns every way of matching a number in `range` at the end of the current part,
+    /// from longest to shortest.
+    pub fn match_number(self, range: &NumRange) -> Vec<Self> {
+        let mut matches = Vec::new();
+        let this = match self.resume() {
+            Some(this) => this,
+            None => return matches,
+        };
+        let part = this.part;
+        let run = part.iter().rev().take_while(|b| b.is_ascii_digit()).count();
+        for len in (1..=run.min(range.max_digits())).rev() {
+            let start = part.len() - len;
+            let digits = &part[start..];
+            if start > 0 && part[start - 1] == b'-' && range.contains(true, digits) {
+                matches.push(this.clone().truncate(start - 1));
+            }
+            if range.contains(false, digits) {
+                matches.push(this.clone().truncate(start));
             }
-            self = next_ok;
-        }
-        let i = q.iter().collect::<String>().parse::<isize>().ok()?;
-        if i < lower || i > upper {
-            return None;
         }
-        Some(last_ok)
+        matches
     }
 }
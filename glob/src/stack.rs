@@ -1,3 +1,6 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
 use super::{Glob, Matcher, Splitter};
 
 /// A stack for unwrapping globs to match them,
@@ -14,7 +17,7 @@ impl<'a> GlobStack<'a> {
         self.0.push(glob.0.as_slice());
     }
     pub fn add_matcher(&mut self, matcher: &'a Matcher) {
-        self.0.push(std::slice::from_ref(matcher));
+        self.0.push(core::slice::from_ref(matcher));
     }
 
     pub fn next(&mut self) -> Option<&'a Matcher> {
@@ -30,35 +33,92 @@ impl<'a> GlobStack<'a> {
     }
 }
 
+/// Observer for the steps taken while matching.
+///
+/// Used for explaining matches. Matching with `()` as the trace does no extra work.
+pub trait Trace<'b> {
+    /// Returns a value that can later be passed to [`Trace::rollback`].
+    fn checkpoint(&self) -> usize;
+    /// Discards all steps taken since the provided checkpoint.
+    fn rollback(&mut self, checkpoint: usize);
+    /// Records a successful step, where `consumed` is the splitter position after the step.
+    ///
+    /// For alternations, `branch` is the option being tried.
+    fn step(&mut self, matcher: &'b Matcher, branch: Option<&'b Glob>, consumed: usize);
+    /// Records a failed step, where `consumed` is the splitter position before the step.
+    fn fail(&mut self, matcher: &'b Matcher, consumed: usize);
+}
+
+impl<'b> Trace<'b> for () {
+    #[inline]
+    fn checkpoint(&self) -> usize {
+        0
+    }
+    #[inline]
+    fn rollback(&mut self, _: usize) {}
+    #[inline]
+    fn step(&mut self, _: &'b Matcher, _: Option<&'b Glob>, _: usize) {}
+    #[inline]
+    fn fail(&mut self, _: &'b Matcher, _: usize) {}
+}
+
 enum SavePoint<'a, 'b> {
-    Rewind(Splitter<'a>, GlobStack<'b>, &'b Matcher),
-    Alts(Splitter<'a>, GlobStack<'b>, &'b [Glob]),
+    Rewind(Splitter<'a>, GlobStack<'b>, &'b Matcher, usize),
+    Alts(Splitter<'a>, GlobStack<'b>, &'b Matcher, &'b [Glob], usize),
+    Resume(Splitter<'a>, GlobStack<'b>, &'b Matcher, usize),
 }
 
 /// A stack for saving and restoring state.
-pub struct SaveStack<'a, 'b> {
+pub struct SaveStack<'a, 'b, T> {
     globs: GlobStack<'b>,
     stack: Vec<SavePoint<'a, 'b>>,
+    trace: T,
 }
 
-impl<'a, 'b> SaveStack<'a, 'b> {
-    pub fn new(_: &Splitter<'a>, glob: &'b Glob) -> SaveStack<'a, 'b> {
+impl<'a, 'b, T: Trace<'b>> SaveStack<'a, 'b, T> {
+    pub fn new(_: &Splitter<'a>, glob: &'b Glob, trace: T) -> SaveStack<'a, 'b, T> {
         SaveStack {
             globs: GlobStack::new(glob),
             stack: Vec::<SavePoint<'a, 'b>>::new(),
+            trace,
         }
     }
     pub fn globs(&mut self) -> &mut GlobStack<'b> {
         &mut self.globs
     }
+    pub fn trace(&mut self) -> &mut T {
+        &mut self.trace
+    }
     pub fn add_rewind(&mut self, splitter: Splitter<'a>, matcher: &'b Matcher) {
-        self.stack
-            .push(SavePoint::Rewind(splitter, self.globs.clone(), matcher))
+        let checkpoint = self.trace.checkpoint();
+        self.stack.push(SavePoint::Rewind(
+            splitter,
+            self.globs.clone(),
+            matcher,
+            checkpoint,
+        ))
+    }
+    /// Saves another way that `matcher`, which has just been matched, could have matched.
+    pub fn add_resume(&mut self, splitter: Splitter<'a>, matcher: &'b Matcher) {
+        let checkpoint = self.trace.checkpoint();
+        self.stack.push(SavePoint::Resume(
+            splitter,
+            self.globs.clone(),
+            matcher,
+            checkpoint,
+        ))
     }
-    pub fn add_alts(&mut self, splitter: Splitter<'a>, matcher: &'b [Glob]) {
-        if let Some((first, rest)) = matcher.split_first() {
-            self.stack
-                .push(SavePoint::Alts(splitter, self.globs.clone(), rest));
+    pub fn add_alts(&mut self, splitter: Splitter<'a>, any: &'b Matcher, options: &'b [Glob]) {
+        if let Some((first, rest)) = options.split_first() {
+            let checkpoint = self.trace.checkpoint();
+            self.trace.step(any, Some(first), splitter.consumed());
+            self.stack.push(SavePoint::Alts(
+                splitter,
+                self.globs.clone(),
+                any,
+                rest,
+                checkpoint,
+            ));
             self.globs().add_glob(first);
         }
     }
@@ -67,22 +127,32 @@ impl<'a, 'b> SaveStack<'a, 'b> {
         loop {
             // There's a continue in here, don't panic.
             break match self.stack.pop()? {
-                SavePoint::Rewind(splitter, globs, matcher) => {
-                    self.stack.pop();
+                SavePoint::Rewind(splitter, globs, matcher, checkpoint) => {
                     self.globs = globs;
                     self.globs.add_matcher(matcher);
+                    self.trace.rollback(checkpoint);
+                    Some(splitter)
+                }
+                SavePoint::Resume(splitter, globs, matcher, checkpoint) => {
+                    self.globs = globs;
+                    self.trace.rollback(checkpoint);
+                    self.trace.step(matcher, None, splitter.consumed());
                     Some(splitter)
                 }
-                SavePoint::Alts(splitter, globs, alts) => {
+                SavePoint::Alts(splitter, globs, any, alts, checkpoint) => {
                     self.globs = globs;
+                    self.trace.rollback(checkpoint);
                     if let Some((glob, rest)) = alts.split_first() {
                         if !rest.is_empty() {
                             self.stack.push(SavePoint::Alts(
                                 splitter.clone(),
                                 self.globs.clone(),
+                                any,
                                 rest,
+                                checkpoint,
                             ));
                         }
+                        self.trace.step(any, Some(glob), splitter.consumed());
                         self.globs.add_glob(glob);
                         Some(splitter)
                     } else {
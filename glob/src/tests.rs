@@ -5,9 +5,39 @@ pub fn test<'a, 'b>(
 ) {
     use crate::Glob;
     let glob = Glob::new(pattern);
+    let canonical = glob.to_string();
+    assert_eq!(
+        Glob::new(&canonical),
+        glob,
+        "`{}` did not reparse to the same glob as `{}`",
+        canonical,
+        pattern
+    );
+    #[cfg(not(feature = "no_std"))]
+    glob.compile();
+    let agrees = |path: &str| {
+        let components = crate::path::Components::from_str(path);
+        let expected = crate::matcher::matches(components, &glob).is_some();
+        #[cfg(not(feature = "no_std"))]
+        assert_eq!(
+            glob.matches(path),
+            expected,
+            "automaton and backtracking disagree on `{}` for pattern `{}`",
+            path,
+            pattern
+        );
+        assert_eq!(
+            glob.matches_str(path),
+            expected,
+            "`matches_str` and backtracking disagree on `{}` for pattern `{}`",
+            path,
+            pattern
+        );
+    };
     for path in valid {
+        agrees(path);
         assert!(
-            glob.matches(path),
+            glob.matches_str(path),
             "`{}` didn't match pattern `{}`; chain: {:?}",
             path,
             pattern,
@@ -15,8 +45,9 @@ pub fn test<'a, 'b>(
         )
     }
     for path in invalid {
+        agrees(path);
         assert!(
-            !glob.matches(path),
+            !glob.matches_str(path),
             "`{}` wrongly matched pattern `{}`; chain {:?}",
             path,
             pattern,
@@ -94,6 +125,11 @@ fn doublestar() {
     );
 }
 
+#[test]
+fn doublestar_then_star() {
+    test("/a*x**c", ["/ayx/c", "/ax/y/c"], ["/a/yx/c"]);
+}
+
 #[test]
 fn charclass_basic() {
     test("[a]", ["/a"], ["/aa", "/b"]);
@@ -131,6 +167,49 @@ fn numrange() {
     test("{2..-1}", ["/2", "/1", "/0", "/-1"], ["/-2"]);
 }
 
+#[test]
+fn numrange_leading_zeros() {
+    test(
+        "{3..120}",
+        ["/3", "/15", "/60", "/120"],
+        ["/1", "/5a", "/121", "/060"],
+    );
+    test("{00..99}", ["/00", "/07", "/99"], ["/7", "/007", "/100"]);
+    test("{1..010}", ["/001", "/010"], ["/1", "/10", "/0010"]);
+    test(
+        "{-05..5}",
+        ["/-05", "/00", "/05"],
+        ["/-5", "/5", "/000", "/-00"],
+    );
+}
+
+#[test]
+fn numrange_sign() {
+    test(
+        "file-{1..10}",
+        ["/file-5", "/file-10"],
+        ["/file5", "/file-0"],
+    );
+    test("{-5..-1}", ["/-3"], ["/3", "/--3"]);
+    test("{-1..1}", ["/-1", "/0", "/1"], ["/-0", "/+1"]);
+    test("1{1..3}", ["/13", "/11"], ["/113", "/14"]);
+}
+
+#[test]
+fn numrange_large() {
+    test("{1..2000}", ["/1001", "/2000"], ["/2001", "/0"]);
+    test(
+        "v{1..99999999999999999999999}",
+        ["/v12345678901234567890", "/v99999999999999999999999"],
+        ["/v100000000000000000000000", "/v0"],
+    );
+    test(
+        "{-18446744073709551616..18446744073709551616}",
+        ["/-18446744073709551616", "/0", "/18446744073709551615"],
+        ["/18446744073709551617"],
+    );
+}
+
 #[test]
 fn alt_basic() {
     test("{}", ["/{}"], ["/"]);
@@ -165,3 +244,641 @@ fn alt_nested() {
 fn alt_empty() {
     test("a{b,,c}", ["/a", "/ab", "/ac"], []);
 }
+
+#[test]
+fn alt_duplicate() {
+    test("{a,a}", ["/a"], ["/{a,a}", "/aa"]);
+    test("x{,}", ["/x"], ["/x{,}"]);
+}
+
+#[test]
+fn display() {
+    let cases = [
+        ("", "*"),
+        ("foo", "foo"),
+        ("/foo", "/foo"),
+        ("foo/bar", "/foo/bar"),
+        ("foo/", "/foo/*"),
+        ("**/*.rs", "/**/*.rs"),
+        ("a[b/]c", "/a\\[b/]c"),
+        ("[!a-z]", "[!a-z]"),
+        ("[ab-]", "[\\-ab]"),
+        ("[a]", "a"),
+        ("{2..-1}", "{-1..2}"),
+        ("{00..5}", "{00..05}"),
+        ("{-3..007}", "{-003..007}"),
+        ("{c,a{b},,a}", "{,a,a\\{b\\},c}"),
+        ("{a,a}", "a"),
+        ("{foo", "\\{foo"),
+        ("\\*\\?", "\\*\\?"),
+    ];
+    for (pattern, expected) in cases {
+        assert_eq!(
+            crate::Glob::new(pattern).to_string(),
+            expected,
+            "unexpected canonical form of `{}`",
+            pattern
+        );
+    }
+}
+
+/// Writes out the parts of a pattern, e.g. `^'src'/**/*'.rs'` for `/src/**/*.rs`.
+fn write_parts(parts: crate::Parts<'_>, out: &mut String) {
+    use crate::Part;
+    for part in parts {
+        match part {
+            Part::Start => out.push('^'),
+            Part::Separator => out.push('/'),
+            Part::Literal(s) => out.push_str(&format!("'{}'", s)),
+            Part::AnyChar => out.push('?'),
+            Part::AnySeq => out.push('*'),
+            Part::AnyPath => out.push_str("**"),
+            Part::CharClass(chars, negated) => {
+                out.push('[');
+                if negated {
+                    out.push('!');
+                }
+                out.extend(chars);
+                out.push(']');
+            }
+            Part::Range(lower, upper, width) => {
+                out.push_str(&format!("{{{}..{}:{:?}}}", lower, upper, width));
+            }
+            Part::Alternation(options) => {
+                out.push('{');
+                for (idx, option) in options.enumerate() {
+                    if idx != 0 {
+                        out.push('|');
+                    }
+                    write_parts(option, out);
+                }
+                out.push('}');
+            }
+        }
+    }
+}
+
+#[test]
+fn parts() {
+    let cases = [
+        ("", "*"),
+        ("*.rs", "*'.rs'"),
+        ("/src/**/*.rs", "^'src'/**/*'.rs'"),
+        ("foo/", "^'foo'/*"),
+        ("a***b", "'a'**'b'"),
+        ("a*?*b", "'a'*?*'b'"),
+        ("[!a-c]x[-]", "[!abc]'x-'"),
+        ("{01..3}{-2..2}", "{1..3:Some(2)}{-2..2:None}"),
+        ("{/a,b}c", "^{/'a'|'b'}'c'"),
+        ("{a,{b,c}d}", "{'a'|{'b'|'c'}'d'}"),
+    ];
+    for (pattern, expected) in cases {
+        let mut out = String::new();
+        write_parts(crate::Glob::new(pattern).parts(), &mut out);
+        assert_eq!(out, expected, "unexpected parts of `{}`", pattern);
+    }
+}
+
+fn explain<'a>(pattern: &str, path: &str, expected: impl IntoIterator<Item = &'a str>) {
+    let glob = crate::Glob::new(pattern);
+    let explanation = glob.explain_str(path);
+    #[cfg(not(feature = "no_std"))]
+    assert_eq!(glob.explain(path), explanation);
+    let captures = explanation.captures().unwrap_or_else(|| {
+        panic!(
+            "`{}` didn't match pattern `{}`: {:?}",
+            path,
+            pattern,
+            explanation.mismatch()
+        )
+    });
+    let captured: Vec<&str> = captures
+        .iter()
+        .map(|capture| &explanation.path()[capture.span.clone()])
+        .collect();
+    assert_eq!(
+        captured,
+        expected.into_iter().collect::<Vec<_>>(),
+        "unexpected captures for `{}` matching `{}`",
+        path,
+        pattern
+    );
+}
+
+#[test]
+fn explain_captures() {
+    explain("*.rs", "/src/lib.rs", ["lib"]);
+    explain("**/*.{rs,toml}", "/a/b/c.toml", ["a/b", "c", "toml"]);
+    explain("a/**", "/a/b/c", ["b/c"]);
+    explain("file{1..10}?[a-c]", "/x/file7\u{e9}b", ["7", "\u{e9}", "b"]);
+    explain("{a,b{c,d}}*", "/bdxy", ["bd", "d", "xy"]);
+}
+
+#[test]
+fn explain_range() {
+    use crate::Element;
+    let glob = crate::Glob::new("v{007..-12}");
+    let explanation = glob.explain_str("/v-003");
+    let captures = explanation.captures().expect("should have matched");
+    assert_eq!(captures[0].element, Element::Range("-12", "7"));
+    assert_eq!(&explanation.path()[captures[0].span.clone()], "-003");
+}
+
+#[test]
+fn explain_mismatch() {
+    use crate::Element;
+    let glob = crate::Glob::new("/*.rs");
+    let explanation = glob.explain_str("/x/y.rs");
+    let mismatch = explanation.mismatch().expect("should not have matched");
+    assert_eq!(mismatch.element, Some(Element::Start));
+    assert_eq!(&explanation.path()[..mismatch.position], "/x/");
+
+    let glob = crate::Glob::new("{foo,bar}.txt");
+    let explanation = glob.explain_str("/baz.txt");
+    let mismatch = explanation.mismatch().expect("should not have matched");
+    assert_eq!(mismatch.element, Some(Element::Literal("bar")));
+    assert_eq!(mismatch.position, 4);
+}
+
+#[test]
+fn anychar_unicode() {
+    test("?.txt", ["/\u{e9}.txt", "/a.txt"], ["/.txt", "/ab.txt"]);
+}
+
+#[cfg(feature = "graphemes")]
+#[test]
+fn graphemes() {
+    // U+0301 is a combining acute accent.
+    test(
+        "?.txt",
+        ["/e\u{301}.txt", "/\u{e9}.txt"],
+        ["/e\u{301}e.txt"],
+    );
+    // Classes match a cluster as a whole, so negated classes match
+    // every cluster that the class does not.
+    test("[a-z]", ["/e"], ["/e\u{301}", "/\u{e9}"]);
+    test("[!a-z]x", ["/\u{e9}x", "/e\u{301}x"], ["/ex"]);
+    test("[\u{e9}]", ["/\u{e9}"], ["/e\u{301}"]);
+    // A family emoji made of three emoji joined by U+200D (ZWJ).
+    test(
+        "a?b",
+        ["/a\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}b"],
+        ["/a\u{1f468}\u{1f469}b"],
+    );
+}
+
+#[cfg(not(feature = "graphemes"))]
+#[test]
+fn graphemes_disabled() {
+    test("?.txt", ["/\u{e9}.txt"], ["/e\u{301}.txt"]);
+    test("??.txt", ["/e\u{301}.txt"], []);
+}
+
+fn syntax_errors(pattern: &str, expected: &[(crate::SyntaxErrorKind, std::ops::Range<usize>)]) {
+    let (glob, errors) = crate::Glob::new_with_errors(pattern);
+    assert_eq!(glob, crate::Glob::new(pattern));
+    let errors: Vec<_> = errors.into_iter().map(|e| (e.kind, e.span)).collect();
+    assert_eq!(errors, expected, "unexpected errors for `{}`", pattern);
+    assert_eq!(
+        crate::Glob::new_strict(pattern)
+            .err()
+            .map(|e| (e.kind, e.span)),
+        expected.first().cloned()
+    );
+}
+
+#[test]
+fn syntax_errors_none() {
+    for pattern in [
+        "",
+        "*.rs",
+        "**/{a,b}/[!c-d]?",
+        "{1..3}",
+        "a{,b}",
+        "foo,bar",
+        "\\{",
+    ] {
+        syntax_errors(pattern, &[]);
+    }
+}
+
+#[test]
+fn syntax_errors_charclass() {
+    use crate::SyntaxErrorKind::*;
+    syntax_errors("a[", &[(UnclosedCharClass, 1..2)]);
+    syntax_errors("a[bc", &[(UnclosedCharClass, 1..4)]);
+    syntax_errors("a[b/]c", &[(UnclosedCharClass, 1..3)]);
+    syntax_errors("x/[]", &[(EmptyCharClass, 2..4)]);
+}
+
+#[test]
+fn syntax_errors_alt() {
+    use crate::SyntaxErrorKind::*;
+    syntax_errors("{a,", &[(UnclosedAlternation, 0..3)]);
+    syntax_errors("a}", &[(UnmatchedBrace, 1..2)]);
+    syntax_errors("x{}", &[(EmptyAlternation, 1..3)]);
+    syntax_errors("{foo}.rs", &[(SingleAlternative, 0..5)]);
+    syntax_errors(
+        "{a{b,c}/{d",
+        &[(UnclosedAlternation, 0..10), (UnclosedAlternation, 8..10)],
+    );
+}
+
+#[test]
+fn syntax_errors_escape() {
+    use crate::SyntaxErrorKind::*;
+    syntax_errors("a\\", &[(DanglingEscape, 1..2)]);
+    syntax_errors("a\\/b", &[(DanglingEscape, 1..2)]);
+}
+
+#[test]
+fn wildcard_before_sep() {
+    test("*/a", ["/b/a", "/bc/a"], ["/b/bc/a", "/bc/b"]);
+    test("?/a", ["/b/a", "b/a"], ["/bc/a"]);
+    test("[ab]/c", ["/a/c", "/b/c"], ["/c/c", "/ab/c"]);
+    test("[!a]/c", ["/b/c"], ["/a/c", "a/b/c"]);
+    test("{1..3}/a", ["/2/a", "3/a"], ["/4/a", "/b/3/a"]);
+    test("a[!b]c", ["/axc"], ["/a/c", "/abc"]);
+}
+
+fn analysis(a: &str, b: &str, subset: bool, overlaps: bool) {
+    use crate::Glob;
+    let (glob_a, glob_b) = (Glob::new(a), Glob::new(b));
+    assert_eq!(
+        glob_a.is_subset_of(&glob_b),
+        subset,
+        "wrong subset result for `{}` and `{}`",
+        a,
+        b
+    );
+    assert_eq!(
+        glob_a.overlaps(&glob_b),
+        overlaps,
+        "wrong overlap result for `{}` and `{}`",
+        a,
+        b
+    );
+    assert_eq!(
+        glob_b.overlaps(&glob_a),
+        overlaps,
+        "overlap is not symmetric for `{}` and `{}`",
+        a,
+        b
+    );
+}
+
+#[test]
+fn analysis_literal() {
+    analysis("foo", "foo", true, true);
+    analysis("foo", "bar", false, false);
+    analysis("foo", "*", true, true);
+    analysis("*", "foo", false, true);
+    analysis("src/foo", "foo", true, true);
+    analysis("foo", "src/foo", false, true);
+    analysis("/a/*", "/b/*", false, false);
+}
+
+#[test]
+fn analysis_anyseq() {
+    analysis("*.rs", "*", true, true);
+    analysis("src/*.rs", "*.rs", true, true);
+    analysis("/*.rs", "**.rs", true, true);
+    analysis("**.rs", "/*.rs", false, true);
+    analysis("a/*/b", "a/**/b", true, true);
+    analysis("a/**/b", "a/*/b", false, true);
+    analysis("a/*", "*/b", false, true);
+    analysis("*.c", "*.h", false, false);
+    analysis("a?", "a*", true, true);
+    analysis("a*", "a?", false, true);
+}
+
+#[test]
+fn analysis_charclass() {
+    analysis("[a-c]", "[a-z]", true, true);
+    analysis("[!a-z]", "[!a-c]", true, true);
+    analysis("[!a]", "[!b]", false, true);
+    analysis("[!a]", "a", false, false);
+    analysis("[ab]", "{a,b}", true, true);
+    analysis("{a,b}", "[ab]", true, true);
+    analysis("[!a]", "?", true, true);
+}
+
+#[test]
+fn charclass_negated_single() {
+    test("[!a]", ["/b", "/-"], ["/a", "/ab"]);
+}
+
+#[test]
+fn analysis_range() {
+    analysis("{2..5}", "[0-9]", true, true);
+    analysis("{1..20}", "[0-9]", false, true);
+    analysis("{1..9}", "{1..20}", true, true);
+    analysis("{-3..3}", "{-5..5}", true, true);
+    analysis("{0..10}", "{1..10}", false, true);
+    analysis("{1..9}", "{10..20}", false, false);
+    analysis("{1..3}x", "*x", true, true);
+    analysis("x{1..3}", "x[1-3]", true, true);
+    analysis("x[1-3]", "x{1..3}", true, true);
+    analysis("{1..20}", "1?", false, true);
+    analysis("{00..99}", "[0-9][0-9]", true, true);
+    analysis("{0..99}", "[0-9][0-9]", false, true);
+    analysis("{01..09}", "{1..9}", false, false);
+    analysis("{-5..-1}", "-[1-5]", true, true);
+}
+
+#[test]
+fn analysis_alt() {
+    analysis("{a,b}.{c,h}", "*.{c,h,cpp}", true, true);
+    analysis("*.{c,h}", "include/*", false, true);
+    analysis("{a,b/c}", "*", true, true);
+    analysis("{a,b/c}", "/*", false, true);
+    analysis("{a,b/c}", "{a,c}", true, true);
+    analysis("{,a}b", "*b", true, true);
+}
+
+#[cfg(not(feature = "no_std"))]
+/// A directory under the system's temporary directory that is removed when dropped.
+struct TempTree(std::path::PathBuf);
+
+#[cfg(not(feature = "no_std"))]
+impl TempTree {
+    /// Creates a directory that is unique to the test `name` and this process,
+    /// containing an empty file at each of `files`.
+    fn new(name: &str, files: &[&str]) -> TempTree {
+        let dir = std::env::temp_dir().join(format!("ec4rs_glob-{}-{}", name, std::process::id()));
+        // Remove anything left behind by a previous process with the same ID.
+        let _ = std::fs::remove_dir_all(&dir);
+        for file in files {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        TempTree(dir)
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl Drop for TempTree {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+fn walk(name: &str, files: &[&str], cases: &[(&str, &[&str])]) {
+    let tree = TempTree::new(name, files);
+    let base = &tree.0;
+    for (pattern, expected) in cases {
+        let found: Vec<_> = crate::Glob::new(pattern)
+            .walk(base)
+            .map(|path| path.unwrap().strip_prefix(base).unwrap().to_owned())
+            .collect();
+        let expected: Vec<_> = expected.iter().map(std::path::PathBuf::from).collect();
+        assert_eq!(found, expected, "wrong files for pattern `{}`", pattern);
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+#[test]
+fn walk_files() {
+    let files = [
+        "a.rs",
+        "b.txt",
+        "src/lib.rs",
+        "src/bin/main.rs",
+        "src/bin/x/deep.rs",
+        "tests/a.rs",
+        "tests/data/a.rs",
+        "doc/1/a.md",
+        "doc/2/a.md",
+        "doc/30/a.md",
+    ];
+    walk(
+        "walk_files",
+        &files,
+        &[
+            (
+                "*.rs",
+                &[
+                    "a.rs",
+                    "src/bin/main.rs",
+                    "src/bin/x/deep.rs",
+                    "src/lib.rs",
+                    "tests/a.rs",
+                    "tests/data/a.rs",
+                ],
+            ),
+            ("/*.rs", &["a.rs"]),
+            ("src/*.rs", &["src/lib.rs"]),
+            (
+                "src/**.rs",
+                &["src/bin/main.rs", "src/bin/x/deep.rs", "src/lib.rs"],
+            ),
+            (
+                "src/**/*.rs",
+                &["src/bin/main.rs", "src/bin/x/deep.rs", "src/lib.rs"],
+            ),
+            ("src/bin/main.rs", &["src/bin/main.rs"]),
+            ("{src,tests}/*.rs", &["src/lib.rs", "tests/a.rs"]),
+            ("*/a.rs", &["a.rs", "tests/a.rs"]),
+            ("doc/{1..10}/*", &["doc/1/a.md", "doc/2/a.md"]),
+            ("missing/*", &[]),
+        ],
+    );
+}
+
+#[cfg(not(feature = "no_std"))]
+#[test]
+fn walk_plan() {
+    use crate::walk::Plan;
+    let start = |pattern: &str| {
+        let plan = Plan::new(&crate::Glob::new(pattern));
+        let position = plan.start();
+        (plan, position)
+    };
+    let (plan, root) = start("src/*/x.rs");
+    assert_eq!(plan.literal(&root), Some("src"));
+    assert!(plan.advance(&root, "lib".as_ref()).is_empty());
+    let src = plan.advance(&root, "src".as_ref());
+    assert_eq!(plan.literal(&src), None);
+    let bin = plan.advance(&src, "bin".as_ref());
+    assert!(!bin.is_empty());
+    assert!(plan.advance(&bin, "x".as_ref()).is_empty());
+
+    let (plan, root) = start("a/*/b/c");
+    let a = plan.advance(&root, "a".as_ref());
+    assert!(!plan.advance(&a, "b".as_ref()).is_empty());
+
+    let (plan, root) = start("src/gen**");
+    let src = plan.advance(&root, "src".as_ref());
+    assert!(plan.advance(&src, "lib".as_ref()).is_empty());
+    let generated = plan.advance(&src, "generated".as_ref());
+    assert!(!plan.advance(&generated, "any".as_ref()).is_empty());
+
+    let (plan, root) = start("*.rs");
+    assert_eq!(plan.literal(&root), None);
+    assert!(!plan.advance(&root, "any".as_ref()).is_empty());
+}
+
+#[test]
+fn backtrack_past_anyseq() {
+    test(
+        "a*b**",
+        ["/ab/xab", "/ab/x/b", "/axb/c"],
+        ["/ac/b", "/b/ba"],
+    );
+    test("/a*{**,x}", ["/ab/xy", "/a/b/c", "/ax"], ["/b/a"]);
+    test(
+        "*a*b*.{x,y}",
+        ["/dir/ab.x", "/aab/ab.y", "/xaxbx.x"],
+        ["/dir/ba.x"],
+    );
+}
+
+#[test]
+fn long_path() {
+    let path = format!(
+        "/{}{}{}.x",
+        "dir/".repeat(30),
+        "a".repeat(40),
+        "bc".repeat(20)
+    );
+    let glob = crate::Glob::new("**/*a*b*c*d*.{x,y,z}");
+    assert!(!glob.matches_str(&path));
+    assert!(glob.matches_str(&format!("{}d.x", path.trim_end_matches(".x"))));
+}
+
+#[test]
+fn str_paths() {
+    let glob = crate::Glob::new("a/*/c");
+    for path in ["a/b/c", "/a/b/c", "./a/b/c", "a//b/c", "a/./b/c/"] {
+        assert!(glob.matches_str(path), "`{}` didn't match", path);
+    }
+    for path in [
+        "", ".", "/", "a/b/b/c", "a/b/c/d", "ab/c", "/a\\b/c", "/x/a/b/c",
+    ] {
+        assert!(!glob.matches_str(path), "`{}` wrongly matched", path);
+    }
+    let glob = crate::Glob::new("/*/c");
+    assert!(glob.matches_str("/b/c"));
+    assert!(glob.matches_str("./b//c"));
+    assert!(!glob.matches_str("x/b/c"));
+    assert!(crate::Glob::new("../*").matches_str("/../a"));
+    let glob = crate::Glob::new("*.rs");
+    assert_eq!(glob.explain_str("src//./lib.rs").path(), "src/lib.rs");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_roundtrip() {
+    use crate::Glob;
+    let patterns = [
+        "*.rs",
+        "/src/**/*.{rs,toml}",
+        "[!a-c]?",
+        "file{-05..5}",
+        "{1..99999999999999999999999}",
+        "{a,b{c,d}}",
+        "",
+    ];
+    for pattern in patterns {
+        let glob = Glob::new(pattern);
+        let json = serde_json::to_string(&glob).unwrap();
+        let loaded: Glob = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, glob, "`{}` changed after round trip", pattern);
+        assert_eq!(loaded.to_string(), glob.to_string());
+    }
+    let loaded: Glob = serde_json::from_str(&serde_json::to_string(&Glob::new("a/*")).unwrap())
+        .expect("should deserialize");
+    assert!(loaded.matches_str("/a/b"));
+    assert!(!loaded.matches_str("/b/a"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_string() {
+    use crate::Glob;
+    let cases = [
+        ("*.rs", "*.rs"),
+        ("a/*", "/a/*"),
+        ("[cac]", "[ac]"),
+        ("{5..-05}", "{-05..05}"),
+        ("foo/[bar", "/foo/\\[bar"),
+    ];
+    for (pattern, written) in cases {
+        let json = serde_json::to_string(&Glob::new(pattern)).unwrap();
+        assert_eq!(json, serde_json::to_string(written).unwrap());
+        let loaded: Glob = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, Glob::new(pattern));
+    }
+    // Any string loads as the pattern it is, so a serialized glob can be written by hand.
+    let loaded: Glob = serde_json::from_str(r#""**/{a,b}""#).unwrap();
+    assert_eq!(loaded, Glob::new("**/{a,b}"));
+    assert!(serde_json::from_str::<Glob>(r#"["End","End","Sep"]"#).is_err());
+}
+
+#[test]
+fn step_limit() {
+    let backtrack = |glob: &crate::Glob, path: &str, limit| {
+        let components = crate::path::Components::from_str(path);
+        crate::matcher::matches_limited(components, glob, limit).map(|matched| matched.is_some())
+    };
+    let glob = crate::Glob::new("b*a*a*a*a*a*a");
+    let path = "a".repeat(30);
+    let error = backtrack(&glob, &path, 100).unwrap_err();
+    assert_eq!(error.limit(), 100);
+    assert_eq!(error.to_string(), "gave up matching after 100 steps");
+    assert_eq!(backtrack(&glob, &path, usize::MAX), Ok(false));
+    let matching = format!("b{}", path);
+    assert_eq!(backtrack(&glob, &matching, 100), Ok(true));
+    // The automaton never gives up.
+    #[cfg(not(feature = "no_std"))]
+    {
+        assert!(glob.compile());
+        assert_eq!(glob.try_matches(&path, 0), Ok(false));
+        assert_eq!(glob.try_matches_str(&matching, 0), Ok(true));
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+#[test]
+fn automaton_after_repeated_matches() {
+    let glob = crate::Glob::new("b*a*a*a*a*a*a");
+    let path = "a".repeat(30);
+    // Matching starts out backtracking, which gives up here.
+    assert!(glob.try_matches_str(&path, 100).is_err());
+    let gave_up = (0..100)
+        .filter(|_| glob.try_matches_str(&path, 100).is_err())
+        .count();
+    assert!(gave_up < 100, "the automaton was never built");
+    assert_eq!(glob.try_matches_str(&path, 0), Ok(false));
+    // Options of alternations are never matched on their own, so they get no automaton.
+    let glob = crate::Glob::new("{a*b,c}");
+    for option in &glob.0 {
+        if let crate::Matcher::Any(options) = option {
+            assert!(options.as_slice().iter().all(|option| !option.compile()));
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+#[test]
+fn may_match_under() {
+    let glob = crate::Glob::new("src/**/*.rs");
+    for dir in ["", "/", "src", "/src/a", "src/a/b/c", "./src"] {
+        assert!(glob.may_match_under(dir), "`{}` was pruned", dir);
+    }
+    for dir in ["tests", "/lib/src", "srcs"] {
+        assert!(!glob.may_match_under(dir), "`{}` wasn't pruned", dir);
+    }
+    let glob = crate::Glob::new("a/*/b/c");
+    assert!(glob.may_match_under("a/x/b"));
+    assert!(glob.may_match_under("a/b"));
+    assert!(!glob.may_match_under("a/x/c"));
+    assert!(!glob.may_match_under("a/x/b/c"));
+    let glob = crate::Glob::new("docs/api**");
+    assert!(glob.may_match_under("docs/api-v2/x"));
+    assert!(!glob.may_match_under("docs/guide"));
+    assert!(crate::Glob::new("*.rs").may_match_under("any/where"));
+    assert!(crate::Glob::new("{a,b}/c").may_match_under("b"));
+}
@@ -0,0 +1,282 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::{Glob, Matcher};
+
+/// Returns `true` if the matcher can match a `/`.
+fn crosses_sep(matcher: &Matcher) -> bool {
+    match matcher {
+        Matcher::Sep | Matcher::AnySeq(true) => true,
+        Matcher::Any(options) => options
+            .as_slice()
+            .iter()
+            .any(|option| option.0.iter().any(crosses_sep)),
+        _ => false,
+    }
+}
+
+/// Returns `true` if the matchers can match an empty string.
+fn nullable(matchers: &[Matcher]) -> bool {
+    matchers.iter().all(|matcher| match matcher {
+        Matcher::AnySeq(_) => true,
+        Matcher::Any(options) => options.as_slice().iter().any(|option| nullable(&option.0)),
+        _ => false,
+    })
+}
+
+/// The set of places in a pattern that a directory can correspond to.
+#[derive(Clone, Debug, Default)]
+pub struct Position {
+    /// Indices of the next path component's pattern.
+    components: Vec<usize>,
+    /// Whether anything below the directory could match.
+    anywhere: bool,
+}
+
+impl Position {
+    /// Returns `true` if nothing below the directory can match.
+    pub fn is_empty(&self) -> bool {
+        !self.anywhere && self.components.is_empty()
+    }
+}
+
+/// The parts of a pattern that constrain which directories may contain matching files.
+pub struct Plan {
+    /// Patterns for each path component up to the first one that can match a `/`.
+    components: Vec<Glob>,
+    nullable: Vec<bool>,
+    /// The literal prefix of the first path component that can match a `/`, if there is one.
+    deep: Option<String>,
+}
+
+impl Plan {
+    pub fn new(glob: &Glob) -> Plan {
+        let mut plan = Plan {
+            components: Vec::new(),
+            nullable: Vec::new(),
+            deep: None,
+        };
+        let matchers = match glob.0.split_first() {
+            Some((Matcher::End, rest)) => rest,
+            // Patterns without a `/` can match at any depth.
+            _ => {
+                plan.deep = Some(String::new());
+                return plan;
+            }
+        };
+        let mut component = vec![Matcher::End];
+        for matcher in matchers {
+            if let Matcher::Sep = matcher {
+                plan.push(std::mem::replace(&mut component, vec![Matcher::End]));
+            } else if crosses_sep(matcher) {
+                plan.deep = Some(match component.get(1) {
+                    Some(Matcher::Suffix(prefix)) => prefix.clone(),
+                    _ => String::new(),
+                });
+                return plan;
+            } else {
+                component.push(matcher.clone());
+            }
+        }
+        plan.push(component);
+        plan
+    }
+
+    fn push(&mut self, component: Vec<Matcher>) {
+        self.nullable.push(nullable(&component[1..]));
+        self.components
+            .push(Glob(component, crate::dfa::Cache::new()));
+    }
+
+    /// Returns the position of the base directory.
+    pub fn start(&self) -> Position {
+        Position {
+            components: vec![0],
+            anywhere: false,
+        }
+    }
+
+    /// Adds the positions reachable by skipping components that can be empty.
+    ///
+    /// The engine lets a `/` match the start of a path component,
+    /// so e.g. `a/*/b` matches `a/b`.
+    fn close(&self, position: &Position) -> Vec<usize> {
+        let mut components = position.components.clone();
+        let mut idx = 0;
+        while let Some(&component) = components.get(idx) {
+            if self.nullable.get(component) == Some(&true) && !components.contains(&(component + 1))
+            {
+                components.push(component + 1);
+            }
+            idx += 1;
+        }
+        components
+    }
+
+    /// Returns the position of a subdirectory named `name` of a directory at `position`.
+    pub fn advance(&self, position: &Position, name: &OsStr) -> Position {
+        let mut next = Position {
+            components: Vec::new(),
+            anywhere: position.anywhere,
+        };
+        if next.anywhere {
+            return next;
+        }
+        for component in self.close(position) {
+            if let Some(glob) = self.components.get(component) {
+                if glob.matches(Path::new(name)) && !next.components.contains(&(component + 1)) {
+                    next.components.push(component + 1);
+                }
+            } else if let Some(prefix) = &self.deep {
+                next.anywhere |= name.to_string_lossy().starts_with(prefix.as_str());
+            }
+        }
+        next
+    }
+
+    /// Returns `true` if a path under a directory at `position` may match.
+    pub fn may_contain(&self, position: &Position) -> bool {
+        position.anywhere
+            || self.deep.is_some() && !position.components.is_empty()
+            || self
+                .close(position)
+                .iter()
+                .any(|&component| component < self.components.len())
+    }
+
+    /// Returns the literal name of the only subdirectory of a directory at `position`
+    /// that can contain matches, if there is one.
+    pub fn literal(&self, position: &Position) -> Option<&str> {
+        let component = match (position.anywhere, position.components.as_slice()) {
+            (false, &[component]) => component,
+            _ => return None,
+        };
+        if component + 1 == self.components.len() && self.deep.is_none() {
+            // The last component matches files, not directories.
+            return None;
+        }
+        match self.components.get(component)?.0.as_slice() {
+            [Matcher::End, Matcher::Suffix(name)] if name != "." && name != ".." => {
+                Some(name.as_str())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns `false` if no path under `dir` can match `glob`.
+pub fn may_match_under(glob: &Glob, dir: &Path) -> bool {
+    use std::path::Component;
+    let plan = Plan::new(glob);
+    let mut position = plan.start();
+    for component in dir.components() {
+        let name = match component {
+            Component::Normal(name) => name,
+            Component::ParentDir => OsStr::new(".."),
+            Component::Prefix(_) | Component::RootDir | Component::CurDir => continue,
+        };
+        position = plan.advance(&position, name);
+        if position.is_empty() {
+            return false;
+        }
+    }
+    plan.may_contain(&position)
+}
+
+struct Dir {
+    path: PathBuf,
+    position: Position,
+    entries: std::vec::IntoIter<io::Result<fs::DirEntry>>,
+}
+
+/// An iterator over the files under a directory that match a [`Glob`].
+///
+/// Created by [`Glob::walk`].
+pub struct Walk<'a> {
+    glob: &'a Glob,
+    plan: Plan,
+    base: PathBuf,
+    stack: Vec<Dir>,
+    pending: Option<(PathBuf, Position)>,
+}
+
+impl<'a> Walk<'a> {
+    pub fn new(glob: &'a Glob, base: &Path) -> Self {
+        let plan = Plan::new(glob);
+        let mut path = PathBuf::new();
+        let mut position = plan.start();
+        // Skip reading directories that only have one possible subdirectory of interest.
+        while let Some(name) = plan.literal(&position) {
+            path.push(name);
+            position = plan.advance(&position, name.as_ref());
+        }
+        Walk {
+            glob,
+            plan,
+            base: base.to_owned(),
+            stack: Vec::new(),
+            pending: Some((path, position)),
+        }
+    }
+
+    fn open(&mut self, path: PathBuf, position: Position) -> io::Result<()> {
+        let mut entries = match fs::read_dir(self.base.join(&path)) {
+            Ok(entries) => entries.collect::<Vec<_>>(),
+            // Directories other than the base may not exist or may have been removed.
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !path.as_os_str().is_empty() => {
+                return Ok(())
+            }
+            Err(e) => return Err(e),
+        };
+        entries.sort_by_key(|entry| entry.as_ref().ok().map(fs::DirEntry::file_name));
+        self.stack.push(Dir {
+            path,
+            position,
+            entries: entries.into_iter(),
+        });
+        Ok(())
+    }
+}
+
+impl Iterator for Walk<'_> {
+    type Item = io::Result<PathBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((path, position)) = self.pending.take() {
+                if let Err(e) = self.open(path, position) {
+                    return Some(Err(e));
+                }
+            }
+            let dir = self.stack.last_mut()?;
+            let entry = match dir.entries.next() {
+                Some(Ok(entry)) => entry,
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+            let name = entry.file_name();
+            let path = dir.path.join(&name);
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => {
+                    let position = self.plan.advance(&dir.position, &name);
+                    if !position.is_empty() {
+                        self.pending = Some((path, position));
+                    }
+                }
+                Ok(_) => {
+                    if self.glob.matches(&path) {
+                        return Some(Ok(entry.path()));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+impl std::iter::FusedIterator for Walk<'_> {}
@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod tests;
+
+use crate::glob::Pattern;
+use crate::linereader::{parse_line, split_line, Line};
+use crate::preamble::Preamble;
+use crate::version::SpecVersion;
+use crate::{ParseError, Section};
+
+/// Parser for the text of an EditorConfig file that is already in memory.
+///
+/// Unlike [`ConfigParser`][crate::ConfigParser], this never copies the text.
+/// Each [`BorrowedSection`] it yields refers to the text it was parsed from,
+/// and can be converted into an owned [`Section`] with [`BorrowedSection::to_section`].
+/// It eagerly parses the preamble on construction.
+///
+/// ```
+/// use ec4rs::BorrowedParser;
+/// let text = "root = true\n[*.rs]\nindent_size = 4\n[*.md]\nindent_size = 2\n";
+/// let mut parser = BorrowedParser::new(text).unwrap();
+/// assert!(parser.preamble.is_root());
+/// let section = parser.next().unwrap().unwrap();
+/// assert_eq!(section.header(), "*.rs");
+/// assert_eq!(section.pairs().collect::<Vec<_>>(), [("indent_size", "4")]);
+/// assert_eq!(parser.next().unwrap().unwrap().line_no(), 4);
+/// assert!(parser.next().is_none());
+/// # #[cfg(feature = "ec4rs_glob")]
+/// assert!(section.to_section::<ec4rs::glob::Glob>().applies_to_str("src/lib.rs"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct BorrowedParser<'a> {
+    /// The preamble for this EditorConfig file.
+    pub preamble: Preamble,
+    /// The unread text, which starts with a section header if it isn't empty.
+    rest: &'a str,
+    /// The number of the last line read.
+    line_no: usize,
+}
+
+impl<'a> BorrowedParser<'a> {
+    /// Constructs a new [`BorrowedParser`] and reads the preamble from `text`.
+    ///
+    /// Returns `Ok` if the preamble was parsed successfully,
+    /// otherwise returns `Err` with the error that occurred during reading.
+    pub fn new(text: &'a str) -> Result<Self, ParseError> {
+        let mut parser = BorrowedParser {
+            preamble: Preamble::new(),
+            rest: text,
+            line_no: 0,
+        };
+        while let Some((line, rest)) = split_line(parser.rest) {
+            let line_no = parser.line_no + 1;
+            match parse_line(line) {
+                Ok(Line::Section(..)) => break,
+                Ok(Line::Pair(key, value, _)) => parser.preamble.read_pair(key, value, line_no),
+                Ok(_) => (),
+                Err(e) => return Err(e),
+            }
+            parser.rest = rest;
+            parser.line_no = line_no;
+        }
+        Ok(parser)
+    }
+
+    /// Returns `true` if there may be another section to read.
+    #[must_use]
+    pub fn has_more(&self) -> bool {
+        !self.rest.is_empty()
+    }
+
+    /// Returns the current line number.
+    ///
+    /// After an error, this is the number of the line that caused it.
+    #[must_use]
+    pub fn line_no(&self) -> usize {
+        self.line_no
+    }
+
+    /// Parses a [`BorrowedSection`], checking that every line in it is valid.
+    pub fn read_section(&mut self) -> Result<BorrowedSection<'a>, ParseError> {
+        let (line, body) = split_line(self.rest).ok_or(ParseError::Eof)?;
+        self.line_no += 1;
+        let header = match parse_line(line) {
+            Ok(Line::Section(header, _)) => header,
+            Ok(_) => return Err(self.fail(ParseError::InvalidLine)),
+            Err(e) => return Err(self.fail(e)),
+        };
+        let line_no = self.line_no;
+        let mut rest = body;
+        while let Some((line, next)) = split_line(rest) {
+            match parse_line(line) {
+                Ok(Line::Section(..)) => break,
+                Ok(_) => (),
+                Err(e) => {
+                    self.line_no += 1;
+                    return Err(self.fail(e));
+                }
+            }
+            rest = next;
+            self.line_no += 1;
+        }
+        self.rest = rest;
+        Ok(BorrowedSection {
+            header,
+            line_no,
+            body: &body[..body.len() - rest.len()],
+        })
+    }
+
+    /// Stops reading after an error.
+    fn fail(&mut self, error: ParseError) -> ParseError {
+        self.rest = "";
+        error
+    }
+}
+
+impl<'a> Iterator for BorrowedParser<'a> {
+    type Item = Result<BorrowedSection<'a>, ParseError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_section() {
+            Ok(r) => Some(Ok(r)),
+            Err(ParseError::Eof) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl core::iter::FusedIterator for BorrowedParser<'_> {}
+
+/// One section of an EditorConfig file, borrowed from its text by a [`BorrowedParser`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BorrowedSection<'a> {
+    header: &'a str,
+    line_no: usize,
+    /// The lines after the header, all of which are valid.
+    body: &'a str,
+}
+
+impl<'a> BorrowedSection<'a> {
+    /// Returns the section header without the brackets, e.g. `*.rs` for `[*.rs]`.
+    #[must_use]
+    pub fn header(&self) -> &'a str {
+        self.header
+    }
+
+    /// Returns the number of the line with the section header.
+    #[must_use]
+    pub fn line_no(&self) -> usize {
+        self.line_no
+    }
+
+    /// Returns an iterator over this section's key-value pairs in the order they were written.
+    ///
+    /// Keys are not lowercased, and keys that occur more than once are yielded each time.
+    #[must_use]
+    pub fn pairs(&self) -> BorrowedPairs<'a> {
+        BorrowedPairs { rest: self.body }
+    }
+
+    /// Converts `self` into an owned [`Section`], parsing the header as a pattern
+    /// and copying the key-value pairs.
+    #[must_use]
+    pub fn to_section<P: Pattern>(&self) -> Section<P> {
+        self.to_section_with_version(SpecVersion::CURRENT)
+    }
+
+    /// As [`BorrowedSection::to_section`], but reads the section
+    /// as it would have been read under the provided version of EditorConfig,
+    /// like [`ConfigParser::with_version`][crate::ConfigParser::with_version] does.
+    #[must_use]
+    pub fn to_section_with_version<P: Pattern>(&self, version: SpecVersion) -> Section<P> {
+        let mut section = Section::new_with_version(self.header, version);
+        for (key, value) in self.pairs() {
+            if let Some(value) = version.read_value(value) {
+                section.insert(key, value);
+            }
+        }
+        section
+    }
+}
+
+impl<P: Pattern> From<BorrowedSection<'_>> for Section<P> {
+    fn from(section: BorrowedSection<'_>) -> Self {
+        section.to_section()
+    }
+}
+
+/// Iterator over the key-value pairs of a [`BorrowedSection`].
+#[derive(Clone, Debug)]
+pub struct BorrowedPairs<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for BorrowedPairs<'a> {
+    type Item = (&'a str, &'a str);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((line, rest)) = split_line(self.rest) {
+            self.rest = rest;
+            if let Ok(Line::Pair(key, value, _)) = parse_line(line) {
+                return Some((key, value));
+            }
+        }
+        None
+    }
+}
+
+impl core::iter::FusedIterator for BorrowedPairs<'_> {}
@@ -0,0 +1,105 @@
+use crate::borrowed::BorrowedParser;
+use crate::ParseError;
+
+#[test]
+fn sections() {
+    let text = "\u{feff}root = true\n; comment\n[a]\nK = 1\n\nk = 2\n[b]\n[c] # c\r\nx=\n";
+    let mut parser = BorrowedParser::new(text).unwrap();
+    assert!(parser.preamble.is_root());
+    assert_eq!(parser.line_no(), 2);
+    let expected: [(_, _, &[_]); 3] = [
+        ("a", 3, &[("K", "1"), ("k", "2")]),
+        ("b", 7, &[]),
+        ("c", 8, &[("x", "")]),
+    ];
+    for (header, line_no, pairs) in expected {
+        let section = parser.next().unwrap().unwrap();
+        assert_eq!(section.header(), header);
+        assert_eq!(section.line_no(), line_no);
+        assert_eq!(section.pairs().collect::<Vec<_>>(), pairs);
+    }
+    assert!(parser.next().is_none());
+    assert!(!parser.has_more());
+    assert_eq!(parser.line_no(), 9);
+}
+
+#[test]
+fn empty() {
+    for text in ["", "\n", "root = false", "# [a]"] {
+        let mut parser = BorrowedParser::new(text).unwrap();
+        assert!(!parser.preamble.is_root());
+        assert!(parser.next().is_none());
+    }
+}
+
+#[test]
+fn errors() {
+    assert!(matches!(
+        BorrowedParser::new("a = b\nnonsense\n[a]"),
+        Err(ParseError::InvalidLine)
+    ));
+    let mut parser = BorrowedParser::new("[a]\nk=v\n[b]\nk=v\nnonsense\n[c]\n").unwrap();
+    assert!(parser.next().unwrap().is_ok());
+    assert!(matches!(
+        parser.next().unwrap(),
+        Err(ParseError::InvalidLine)
+    ));
+    assert_eq!(parser.line_no(), 5);
+    assert!(parser.next().is_none());
+    // As with `ConfigParser`, an invalid header ends the section before it with an error.
+    let mut parser = BorrowedParser::new("[a]\n[]\n").unwrap();
+    assert!(matches!(
+        parser.next().unwrap(),
+        Err(ParseError::InvalidSection(None))
+    ));
+    assert_eq!(parser.line_no(), 2);
+}
+
+#[cfg(feature = "ec4rs_glob")]
+#[test]
+fn to_section_agrees() {
+    use crate::glob::Glob;
+    use crate::{ConfigParser, Section, TextReader};
+    let text = "root=true\nTab_Width = 8\n[*.rs]\nIndent_Size = 4\nindent_size = 2\n[{a,b}/*]\nfoo = bar\n[*]\n";
+    let borrowed = BorrowedParser::new(text).unwrap();
+    let owned = ConfigParser::<_, Glob>::new(TextReader::new(text)).unwrap();
+    assert_eq!(borrowed.preamble, owned.preamble);
+    let mut count = 0;
+    for (borrowed, owned) in borrowed.zip(owned) {
+        let converted: Section<Glob> = borrowed.unwrap().into();
+        let owned = owned.unwrap();
+        assert_eq!(
+            converted.pattern().as_ref().ok(),
+            owned.pattern().as_ref().ok()
+        );
+        assert_eq!(converted.props(), owned.props());
+        count += 1;
+    }
+    assert_eq!(count, 3);
+}
+
+#[cfg(feature = "ec4rs_glob")]
+#[test]
+fn to_section_with_version_agrees() {
+    use crate::glob::Glob;
+    use crate::version::SpecVersion;
+    use crate::{ConfigParser, Section, TextReader};
+    let text = "[{1..3}]\nfoo = bar ; baz\nempty =\n";
+    for version in [SpecVersion::new(0, 8, 0), SpecVersion::CURRENT] {
+        let mut borrowed = BorrowedParser::new(text).unwrap();
+        let converted: Section<Glob> = borrowed
+            .next()
+            .unwrap()
+            .unwrap()
+            .to_section_with_version(version);
+        let owned = ConfigParser::<_, Glob>::new(TextReader::new(text))
+            .unwrap()
+            .with_version(version)
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(converted.props(), owned.props());
+        assert_eq!(converted.applies_to_str("2"), version.has_numeric_ranges());
+        assert_eq!(converted.applies_to_str("2"), owned.applies_to_str("2"));
+    }
+}
@@ -12,7 +12,7 @@ pub trait Cache {
     fn get_shared_string(&self, value: &str) -> SharedString;
 }
 
-impl<C: Cache + ?Sized, T: std::ops::Deref<Target = C>> Cache for T {
+impl<C: Cache + ?Sized, T: core::ops::Deref<Target = C>> Cache for T {
     fn get_shared_string(&self, value: &str) -> SharedString {
         self.deref().get_shared_string(value)
     }
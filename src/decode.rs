@@ -0,0 +1,290 @@
+#[cfg(test)]
+mod tests;
+
+use std::io::{self, BufRead, Read};
+
+use crate::linereader::LineSource;
+use crate::ParseError;
+
+/// How [`DecodingReader`] decodes text that is not in UTF-16.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// UTF-8. Invalid UTF-8 is an [`io::ErrorKind::InvalidData`] error,
+    /// as it is when reading any other [`BufRead`].
+    #[default]
+    Utf8,
+    /// UTF-8, with invalid sequences replaced by `U+FFFD`.
+    Utf8Lossy,
+    /// UTF-8, with each line that isn't valid UTF-8 decoded as ISO-8859-1 (Latin-1) instead.
+    Latin1,
+}
+
+/// A problem found while decoding a line, which [`DecodingReader`] worked around.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct DecodeIssue {
+    line_no: usize,
+    kind: DecodeIssueKind,
+}
+
+/// The kind of [`DecodeIssue`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum DecodeIssueKind {
+    /// The line was not valid UTF-8, and invalid sequences were replaced by `U+FFFD`.
+    InvalidUtf8,
+    /// The line was not valid UTF-8, and was decoded as Latin-1.
+    Latin1,
+    /// The line was not valid UTF-16, and invalid code units were replaced by `U+FFFD`.
+    InvalidUtf16,
+}
+
+impl DecodeIssue {
+    /// Returns the 1-based number of the line with the issue.
+    #[must_use]
+    pub fn line_no(&self) -> usize {
+        self.line_no
+    }
+
+    /// Returns what the issue was.
+    #[must_use]
+    pub fn kind(&self) -> DecodeIssueKind {
+        self.kind
+    }
+}
+
+impl core::fmt::Display for DecodeIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let issue = match self.kind {
+            DecodeIssueKind::InvalidUtf8 => "invalid UTF-8 replaced",
+            DecodeIssueKind::Latin1 => "invalid UTF-8 decoded as Latin-1",
+            DecodeIssueKind::InvalidUtf16 => "invalid UTF-16 replaced",
+        };
+        write!(f, "line {}: {}", self.line_no, issue)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ByteOrder {
+    Little,
+    Big,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Format {
+    /// The start of the text has yet to be checked for a byte order mark.
+    Unknown,
+    Utf16(ByteOrder),
+    /// Anything decoded according to an [`Encoding`].
+    Other,
+}
+
+/// A [`LineSource`] that decodes text from a [`BufRead`] more tolerantly than `BufRead` itself.
+///
+/// Text that starts with a UTF-16 byte order mark is decoded as UTF-16.
+/// Other text is decoded according to an [`Encoding`].
+/// Problems that were worked around are recorded as [`DecodeIssue`]s
+/// rather than being returned as errors.
+///
+/// Spans and offsets reported by parsers are relative to the decoded text.
+///
+/// ```
+/// # #[cfg(feature = "ec4rs_glob")] {
+/// use ec4rs::{ConfigParser, DecodingReader, Encoding, glob::Glob};
+/// let text: &[u8] = b"[caf\xe9]\nk = v\n";
+/// let reader = DecodingReader::new(text).with_encoding(Encoding::Latin1);
+/// let mut parser = ConfigParser::<_, Glob>::new(reader).unwrap();
+/// assert!(parser.next().unwrap().unwrap().applies_to("café"));
+/// let issue = parser.get_ref().issues()[0];
+/// assert_eq!(issue.to_string(), "line 1: invalid UTF-8 decoded as Latin-1");
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DecodingReader<R> {
+    reader: R,
+    encoding: Encoding,
+    format: Format,
+    line_no: usize,
+    bytes: Vec<u8>,
+    issues: Vec<DecodeIssue>,
+}
+
+impl<R> DecodingReader<R> {
+    /// Constructs a new [`DecodingReader`] that decodes text as [`Encoding::Utf8`]
+    /// unless it is in UTF-16.
+    pub fn new(reader: R) -> Self {
+        DecodingReader {
+            reader,
+            encoding: Encoding::Utf8,
+            format: Format::Unknown,
+            line_no: 0,
+            bytes: Vec::new(),
+            issues: Vec::new(),
+        }
+    }
+
+    /// Returns `self` set to decode text that is not in UTF-16 as `encoding`.
+    #[must_use]
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Returns the issues found while decoding the lines read so far.
+    pub fn issues(&self) -> &[DecodeIssue] {
+        &self.issues
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    fn issue(&mut self, kind: DecodeIssueKind) {
+        self.issues.push(DecodeIssue {
+            line_no: self.line_no,
+            kind,
+        });
+    }
+}
+
+impl<R: BufRead> DecodingReader<R> {
+    /// Returns the format of the text, consuming any UTF-16 byte order mark at its start.
+    fn format(&mut self) -> io::Result<Format> {
+        if self.format == Format::Unknown {
+            self.format = match self.reader.fill_buf()? {
+                [0xFF, 0xFE, ..] => Format::Utf16(ByteOrder::Little),
+                [0xFE, 0xFF, ..] => Format::Utf16(ByteOrder::Big),
+                _ => Format::Other,
+            };
+            if self.format != Format::Other {
+                self.reader.consume(2);
+            }
+        }
+        Ok(self.format)
+    }
+
+    /// Reads the next line into `self.bytes`, stopping after about `max` bytes.
+    fn read_raw_line(&mut self, max: u64) -> io::Result<()> {
+        self.bytes.clear();
+        match self.format()? {
+            Format::Utf16(order) => self.read_utf16_line(order, max),
+            _ => (&mut self.reader)
+                .take(max)
+                .read_until(b'\n', &mut self.bytes)
+                .map(drop),
+        }
+    }
+
+    /// Reads UTF-16 code units up to and including the next `\n` into `self.bytes`,
+    /// stopping after about `max` bytes.
+    fn read_utf16_line(&mut self, order: ByteOrder, max: u64) -> io::Result<()> {
+        loop {
+            let read = u64::try_from(self.bytes.len()).unwrap_or(u64::MAX);
+            let mut limited = (&mut self.reader).take(max.saturating_sub(read));
+            if limited.read_until(b'\n', &mut self.bytes)? == 0 {
+                return Ok(());
+            }
+            let len = self.bytes.len();
+            if self.bytes[len - 1] != b'\n' {
+                return Ok(());
+            }
+            match order {
+                // The `\n` is a low byte, and its high byte has yet to be read.
+                ByteOrder::Little if len % 2 == 1 => {
+                    let mut high = [0];
+                    if self.reader.read(&mut high)? == 0 {
+                        return Ok(());
+                    }
+                    self.bytes.push(high[0]);
+                    if high[0] == 0 {
+                        return Ok(());
+                    }
+                }
+                ByteOrder::Big if len % 2 == 0 && self.bytes[len - 2] == 0 => return Ok(()),
+                _ => (),
+            }
+        }
+    }
+
+    fn decode_utf16(&mut self, order: ByteOrder, buf: &mut String) {
+        let units = self.bytes.chunks_exact(2).map(|pair| match order {
+            ByteOrder::Little => u16::from_le_bytes([pair[0], pair[1]]),
+            ByteOrder::Big => u16::from_be_bytes([pair[0], pair[1]]),
+        });
+        let mut valid = self.bytes.len() % 2 == 0;
+        for c in char::decode_utf16(units) {
+            buf.push(c.unwrap_or_else(|_| {
+                valid = false;
+                char::REPLACEMENT_CHARACTER
+            }));
+        }
+        if self.bytes.len() % 2 == 1 {
+            buf.push(char::REPLACEMENT_CHARACTER);
+        }
+        if !valid {
+            self.issue(DecodeIssueKind::InvalidUtf16);
+        }
+    }
+
+    fn decode(&mut self, buf: &mut String) -> Result<(), ParseError> {
+        match std::str::from_utf8(&self.bytes) {
+            Ok(text) => buf.push_str(text),
+            Err(e) => match self.encoding {
+                Encoding::Utf8 => {
+                    return Err(ParseError::Io(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        e,
+                    )))
+                }
+                Encoding::Utf8Lossy => {
+                    buf.push_str(&String::from_utf8_lossy(&self.bytes));
+                    self.issue(DecodeIssueKind::InvalidUtf8);
+                }
+                Encoding::Latin1 => {
+                    buf.extend(self.bytes.iter().copied().map(char::from));
+                    self.issue(DecodeIssueKind::Latin1);
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Decodes the line in `self.bytes` into `buf`.
+    fn decode_line(&mut self, buf: &mut String) -> Result<usize, ParseError> {
+        if self.bytes.is_empty() {
+            return Ok(0);
+        }
+        self.line_no += 1;
+        let start = buf.len();
+        match self.format {
+            Format::Utf16(order) => self.decode_utf16(order, buf),
+            _ => self.decode(buf)?,
+        }
+        Ok(buf.len() - start)
+    }
+}
+
+impl<R: BufRead> LineSource for DecodingReader<R> {
+    fn read_line(&mut self, buf: &mut String) -> Result<usize, ParseError> {
+        self.read_raw_line(u64::MAX).map_err(ParseError::Io)?;
+        self.decode_line(buf)
+    }
+
+    /// Limits the line length after decoding,
+    /// reading no more than about twice `max` bytes of UTF-16.
+    fn read_line_limited(&mut self, buf: &mut String, max: usize) -> Result<usize, ParseError> {
+        // Enough to tell whether the line is too long even if it ends with `\r\n`,
+        // as no encoding decodes to fewer UTF-8 bytes than this per code unit.
+        let units = u64::try_from(max).map_or(u64::MAX, |max| max.saturating_add(2));
+        let max_bytes = match self.format().map_err(ParseError::Io)? {
+            Format::Utf16(_) => units.saturating_mul(2),
+            _ => units,
+        };
+        self.read_raw_line(max_bytes).map_err(ParseError::Io)?;
+        let start = buf.len();
+        let len = self.decode_line(buf)?;
+        crate::limits::check_line(&buf.as_bytes()[start..], max)?;
+        Ok(len)
+    }
+}
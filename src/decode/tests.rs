@@ -0,0 +1,97 @@
+use crate::decode::{DecodeIssue, DecodeIssueKind, DecodingReader, Encoding};
+use crate::linereader::LineSource;
+use crate::ParseError;
+
+fn read_all<R: std::io::BufRead>(
+    reader: &mut DecodingReader<R>,
+) -> Result<Vec<String>, ParseError> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(lines);
+        }
+        lines.push(line);
+    }
+}
+
+fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for unit in std::iter::once(0xFEFF).chain(text.encode_utf16()) {
+        if big_endian {
+            bytes.extend(unit.to_be_bytes());
+        } else {
+            bytes.extend(unit.to_le_bytes());
+        }
+    }
+    bytes
+}
+
+#[test]
+fn utf8() {
+    let text: &[u8] = b"[a]\nk = \xc3\xa9\n";
+    let mut reader = DecodingReader::new(text);
+    assert_eq!(read_all(&mut reader).unwrap(), ["[a]\n", "k = é\n"]);
+    assert!(reader.issues().is_empty());
+}
+
+#[test]
+fn invalid_utf8() {
+    let text: &[u8] = b"[a]\nk = caf\xe9\nj = \xff\xfe\n";
+    let mut reader = DecodingReader::new(text);
+    assert!(
+        matches!(read_all(&mut reader), Err(ParseError::Io(e)) if e.kind() == std::io::ErrorKind::InvalidData)
+    );
+    let mut reader = DecodingReader::new(text).with_encoding(Encoding::Utf8Lossy);
+    assert_eq!(
+        read_all(&mut reader).unwrap(),
+        ["[a]\n", "k = caf\u{fffd}\n", "j = \u{fffd}\u{fffd}\n"]
+    );
+    let kinds: Vec<_> = reader.issues().iter().map(DecodeIssue::kind).collect();
+    assert_eq!(kinds, [DecodeIssueKind::InvalidUtf8; 2]);
+    let mut reader = DecodingReader::new(text).with_encoding(Encoding::Latin1);
+    assert_eq!(
+        read_all(&mut reader).unwrap(),
+        ["[a]\n", "k = café\n", "j = ÿþ\n"]
+    );
+    let line_nos: Vec<_> = reader.issues().iter().map(DecodeIssue::line_no).collect();
+    assert_eq!(line_nos, [2, 3]);
+    assert_eq!(reader.issues()[0].kind(), DecodeIssueKind::Latin1);
+}
+
+#[test]
+fn utf16_lines() {
+    // U+0A0A and U+010A contain a `\n` byte in both byte orders.
+    let text = "[*]\r\nk = \u{a0a}\u{10a}\nj = 😀";
+    for big_endian in [false, true] {
+        let bytes = utf16(text, big_endian);
+        let mut reader = DecodingReader::new(bytes.as_slice());
+        assert_eq!(
+            read_all(&mut reader).unwrap(),
+            ["[*]\r\n", "k = \u{a0a}\u{10a}\n", "j = 😀"]
+        );
+        assert!(reader.issues().is_empty());
+    }
+}
+
+#[test]
+fn invalid_utf16() {
+    let mut bytes = utf16("k = v\n", false);
+    // An unpaired surrogate.
+    bytes.extend([0x00, 0xD8, b'\n', 0x00]);
+    // A trailing odd byte.
+    bytes.push(b'x');
+    let mut reader = DecodingReader::new(bytes.as_slice()).with_encoding(Encoding::Utf8);
+    assert_eq!(
+        read_all(&mut reader).unwrap(),
+        ["k = v\n", "\u{fffd}\n", "\u{fffd}"]
+    );
+    let issues: Vec<_> = reader.issues().iter().map(ToString::to_string).collect();
+    assert_eq!(
+        issues,
+        [
+            "line 2: invalid UTF-16 replaced",
+            "line 3: invalid UTF-16 replaced"
+        ]
+    );
+}
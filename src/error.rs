@@ -1,4 +1,6 @@
+#[cfg(not(feature = "no_std"))]
 use crate::string::Source;
+use alloc::boxed::Box;
 
 /// Possible errors that can occur while parsing EditorConfig data.
 #[derive(Debug)]
@@ -7,28 +9,34 @@ pub enum ParseError {
     /// End-of-file was reached.
     Eof,
     /// An IO read failure occurred.
+    #[cfg(not(feature = "no_std"))]
     Io(std::io::Error),
     /// An invalid line was read.
     InvalidLine,
     /// A line contains a section header,
     /// but either the header is empty or there is non-comment data after it.
     InvalidSection(Option<Box<str>>),
+    /// One of the configured [`Limits`][crate::limits::Limits] was exceeded.
+    LimitExceeded(crate::limits::LimitExceeded),
 }
 
-impl std::fmt::Display for ParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             ParseError::Eof => write!(f, "end of data"),
+            #[cfg(not(feature = "no_std"))]
             ParseError::Io(e) => write!(f, "io failure: {e}"),
             ParseError::InvalidLine => write!(f, "invalid line"),
             ParseError::InvalidSection(None) => write!(f, "empty section header"),
             ParseError::InvalidSection(Some(v)) => {
                 write!(f, "invalid data {:?} after section header", Box::as_ref(v))
             }
+            ParseError::LimitExceeded(e) => write!(f, "{e}"),
         }
     }
 }
 
+#[cfg(not(feature = "no_std"))]
 impl std::error::Error for ParseError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -39,6 +47,7 @@ impl std::error::Error for ParseError {
 }
 
 /// All errors that can occur during operation.
+#[cfg(not(feature = "no_std"))]
 #[derive(Debug)]
 pub enum Error {
     /// An error occured during parsing.
@@ -47,19 +56,18 @@ pub enum Error {
     InvalidCwd(std::io::Error),
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+#[cfg(not(feature = "no_std"))]
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Error::Parse(error, None) => write!(f, "{error}"),
-            Error::Parse(error, Some(source)) => {
-                let (path, line) = source.get();
-                write!(f, "{}:{}: {}", path.to_string_lossy(), line, error)
-            }
+            Error::Parse(error, Some(source)) => write!(f, "{source}: {error}"),
             Error::InvalidCwd(ioe) => write!(f, "invalid cwd: {ioe}"),
         }
     }
 }
 
+#[cfg(not(feature = "no_std"))]
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests;
+
+use crate::limits::{Counts, LimitExceeded, Limits};
+use crate::linereader::{Line, LineReader, LineSource, PairSpans, Span};
+use crate::ParseError;
+
+/// One line of an EditorConfig file, as read by [`EventReader`].
+///
+/// Each part of the line that was parsed comes with its [`Span`] in the file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// A key-value pair before the first section header.
+    PreamblePair(&'a str, &'a str, PairSpans),
+    /// A section header, e.g. `[*.rs]`, without the brackets.
+    SectionStart(&'a str, Span),
+    /// A key-value pair after a section header.
+    Pair(&'a str, &'a str, PairSpans),
+    /// A comment, including its leading `#` or `;`.
+    ///
+    /// Comments after section headers on the same line are not included.
+    Comment(&'a str, Span),
+    /// An empty line, or one containing only whitespace.
+    Blank,
+}
+
+/// Pull-based reader of [`Event`]s from the text of an EditorConfig file.
+///
+/// Unlike [`ConfigParser`][crate::ConfigParser], this reads the preamble as events
+/// and never collects pairs into [`Section`][crate::Section]s,
+/// so sections can be acted upon or skipped as soon as their headers are read.
+/// Keys and values are as written in the file, without any lowercasing.
+/// Events are the same under every version of EditorConfig: headers and values are
+/// never reinterpreted, so inline comments, for example, are left in values.
+/// Use [`ConfigParser::with_version`][crate::ConfigParser::with_version]
+/// to read a file as an older version would.
+///
+/// ```
+/// use ec4rs::{Event, EventReader, ParseError, TextReader};
+/// let mut events = EventReader::new(TextReader::new("root = true\n\n[*.rs]\n# Rust\n"));
+/// let (line_no, event) = events.next_event().unwrap();
+/// assert_eq!(line_no, 1);
+/// let Event::PreamblePair("root", "true", spans) = event else { panic!() };
+/// assert_eq!(spans.value.bytes(), 7..11);
+/// assert!(matches!(events.next_event(), Ok((2, Event::Blank))));
+/// let Ok((3, Event::SectionStart("*.rs", span))) = events.next_event() else { panic!() };
+/// assert_eq!((span.bytes(), span.columns()), (14..18, 1..5));
+/// assert!(matches!(events.next_event(), Ok((4, Event::Comment("# Rust", _)))));
+/// assert!(matches!(events.next_event(), Err(ParseError::Eof)));
+/// ```
+pub struct EventReader<R> {
+    reader: LineReader<R>,
+    in_section: bool,
+    /// Whether the current line should be yielded again by the next call to `next_event`.
+    pending: bool,
+    limits: Limits,
+    counts: Counts,
+    /// The limit that was exceeded, after which nothing more is read.
+    exceeded: Option<LimitExceeded>,
+}
+
+impl<R> EventReader<R> {
+    /// Constructs a new event reader at the start of an EditorConfig file.
+    pub fn new(source: R) -> EventReader<R> {
+        EventReader {
+            reader: LineReader::new(source),
+            in_section: false,
+            pending: false,
+            limits: Limits::NONE,
+            counts: Counts::default(),
+            exceeded: None,
+        }
+    }
+
+    /// Returns `self` set to stop reading once any of `limits` is exceeded.
+    ///
+    /// This should be called before any events are read.
+    /// Once a limit is exceeded, every following call to [`EventReader::next_event`]
+    /// returns the same [`ParseError::LimitExceeded`].
+    /// [`Limits::max_depth`] is not used.
+    #[must_use]
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.reader = self.reader.with_limits(limits);
+        self.limits = limits;
+        self
+    }
+
+    /// Returns the 1-based number of the last line read, or `0` if none have been read.
+    pub fn line_no(&self) -> usize {
+        self.reader.line_no()
+    }
+
+    /// Returns a reference to the underlying line source.
+    pub fn get_ref(&self) -> &R {
+        self.reader.get_ref()
+    }
+
+    /// Makes the next call to [`EventReader::next_event`] yield the last event again.
+    pub(crate) fn unread(&mut self) {
+        self.pending = self.reader.line_no() > 0;
+    }
+}
+
+impl<R: LineSource> EventReader<R> {
+    /// Reads the next line and returns it as an [`Event`] along with its 1-based line number.
+    ///
+    /// Returns [`ParseError::Eof`] once there are no more lines.
+    /// An invalid line results in an error for that line only;
+    /// the following call continues with the next line.
+    pub fn next_event(&mut self) -> Result<(usize, Event<'_>), ParseError> {
+        if let Some(exceeded) = self.exceeded {
+            return Err(ParseError::LimitExceeded(exceeded));
+        }
+        let fresh = !core::mem::replace(&mut self.pending, false);
+        if fresh {
+            if let Err(e) = self.reader.advance() {
+                if let ParseError::LimitExceeded(exceeded) = e {
+                    self.exceeded = Some(exceeded);
+                }
+                return Err(e);
+            }
+        }
+        let line = self.reader.reparse()?;
+        let counted = match line {
+            Line::Section(..) if fresh => self.counts.section(&self.limits),
+            Line::Pair(..) if fresh => self.counts.pair(&self.limits),
+            _ => Ok(()),
+        };
+        if let Err(exceeded) = counted {
+            self.exceeded = Some(exceeded);
+            return Err(ParseError::LimitExceeded(exceeded));
+        }
+        Ok((self.reader.line_no(), to_event(&mut self.in_section, line)))
+    }
+}
+
+/// Classifies a parsed line, noting whether the first section has started.
+fn to_event<'a>(in_section: &mut bool, line: Line<'a>) -> Event<'a> {
+    match line {
+        Line::Section(header, span) => {
+            *in_section = true;
+            Event::SectionStart(header, span)
+        }
+        Line::Pair(key, value, spans) if *in_section => Event::Pair(key, value, spans),
+        Line::Pair(key, value, spans) => Event::PreamblePair(key, value, spans),
+        Line::Comment(text, span) => Event::Comment(text, span),
+        Line::Nothing => Event::Blank,
+    }
+}
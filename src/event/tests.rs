@@ -0,0 +1,106 @@
+use crate::event::{Event, EventReader};
+use crate::{ParseError, TextReader};
+
+fn events(text: &str) -> Vec<Result<(usize, String), String>> {
+    let mut reader = EventReader::new(TextReader::new(text));
+    let mut events = Vec::new();
+    loop {
+        match reader.next_event() {
+            Err(ParseError::Eof) => break events,
+            Err(e) => events.push(Err(e.to_string())),
+            Ok((line_no, event)) => events.push(Ok((line_no, describe(event)))),
+        }
+    }
+}
+
+/// Formats an event without its spans.
+fn describe(event: Event<'_>) -> String {
+    match event {
+        Event::PreamblePair(key, value, _) => format!("PreamblePair({key:?}, {value:?})"),
+        Event::SectionStart(header, _) => format!("SectionStart({header:?})"),
+        Event::Pair(key, value, _) => format!("Pair({key:?}, {value:?})"),
+        Event::Comment(text, _) => format!("Comment({text:?})"),
+        Event::Blank => "Blank".to_owned(),
+    }
+}
+
+#[test]
+fn all_events() {
+    let text =
+        "\u{feff}root = true\n\n  # comment\nfoo=bar\n[*.rs] ; header\n; c\nKey = Value\n \n[a]";
+    let expected = [
+        (1, "PreamblePair(\"root\", \"true\")"),
+        (2, "Blank"),
+        (3, "Comment(\"# comment\")"),
+        (4, "PreamblePair(\"foo\", \"bar\")"),
+        (5, "SectionStart(\"*.rs\")"),
+        (6, "Comment(\"; c\")"),
+        (7, "Pair(\"Key\", \"Value\")"),
+        (8, "Blank"),
+        (9, "SectionStart(\"a\")"),
+    ];
+    let expected: Vec<_> = expected
+        .iter()
+        .map(|(line_no, event)| Ok((*line_no, (*event).to_owned())))
+        .collect();
+    assert_eq!(events(text), expected);
+}
+
+#[test]
+fn empty() {
+    assert!(events("").is_empty());
+    assert_eq!(events("\u{feff}"), [Ok((1, "Blank".to_owned()))]);
+}
+
+#[test]
+fn errors_continue() {
+    assert_eq!(
+        events("[a]\nnot a pair\nk=v\n[]\n"),
+        [
+            Ok((1, "SectionStart(\"a\")".to_owned())),
+            Err("invalid line".to_owned()),
+            Ok((3, "Pair(\"k\", \"v\")".to_owned())),
+            Err("empty section header".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn unread() {
+    let mut reader = EventReader::new(TextReader::new("k=v\n[a]\n"));
+    reader.unread();
+    assert!(matches!(
+        reader.next_event(),
+        Ok((1, Event::PreamblePair("k", "v", _)))
+    ));
+    assert!(matches!(
+        reader.next_event(),
+        Ok((2, Event::SectionStart("a", _)))
+    ));
+    reader.unread();
+    let (line_no, event) = reader.next_event().unwrap();
+    assert_eq!(
+        (line_no, describe(event)),
+        (2, "SectionStart(\"a\")".to_owned())
+    );
+    assert!(matches!(reader.next_event(), Err(ParseError::Eof)));
+}
+
+#[test]
+fn spans() {
+    let mut reader = EventReader::new(TextReader::new("\u{feff}k = v\r\n[*.rs]  # c\n ; end"));
+    let Ok((1, Event::PreamblePair(_, _, spans))) = reader.next_event() else {
+        panic!("expected a pair");
+    };
+    assert_eq!(spans.key.bytes(), 3..4);
+    assert_eq!(spans.separator.bytes(), 5..6);
+    assert_eq!(spans.value.bytes(), 7..8);
+    let Ok((2, Event::SectionStart(_, span))) = reader.next_event() else {
+        panic!("expected a section");
+    };
+    assert_eq!((span.bytes(), span.columns()), (11..15, 1..5));
+    let Ok((3, Event::Comment(_, span))) = reader.next_event() else {
+        panic!("expected a comment");
+    };
+    assert_eq!((span.bytes(), span.columns()), (23..28, 1..6));
+}
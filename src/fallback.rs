@@ -1,3 +1,5 @@
+use alloc::borrow::ToOwned;
+
 use crate::property as prop;
 
 pub fn add_fallbacks(props: &mut crate::Properties, legacy: bool) {
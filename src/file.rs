@@ -1,16 +1,53 @@
+use std::fs::File;
+use std::io::BufReader;
 use std::path::{Path, PathBuf};
 
 use crate::{
-    glob::Pattern, properties::PropertiesSink, string::Source, ConfigParser, Error, ParseError,
-    PropertiesSource, Section,
+    decode::{DecodingReader, Encoding},
+    glob::Pattern,
+    limits::{LimitExceeded, Limits},
+    properties::PropertiesSink,
+    search::{resolve, ErrorPolicy, SearchOptions, SymlinkPolicy},
+    string::Source,
+    ConfigParser, Error, LineSource, ParseError, PropertiesSource, Section,
 };
 
+/// The reader of files that are decoded with a [`DecodingReader`].
+pub(crate) type Decoded = DecodingReader<BufReader<File>>;
+
+/// A [`LineSource`] that [`ConfigFile`] can read an open file with.
+///
+/// This is implemented for [`BufReader`], which reads files as UTF-8,
+/// and for [`DecodingReader`], which decodes them.
+pub trait FileReader: LineSource + Sized {
+    /// Wraps `file`, decoding text that is not in UTF-16 as `encoding`
+    /// if this reader decodes text.
+    fn from_file(file: BufReader<File>, encoding: Encoding) -> Self;
+}
+
+/// Reads files as UTF-8 regardless of the encoding.
+impl FileReader for BufReader<File> {
+    fn from_file(file: BufReader<File>, _: Encoding) -> Self {
+        file
+    }
+}
+
+impl FileReader for DecodingReader<BufReader<File>> {
+    fn from_file(file: BufReader<File>, encoding: Encoding) -> Self {
+        DecodingReader::new(file).with_encoding(encoding)
+    }
+}
+
 /// Convenience wrapper for a [`ConfigParser`] that reads files.
-pub struct ConfigFile<P: Pattern> {
+///
+/// Files are read as UTF-8 by default.
+/// To read files in UTF-16 or other encodings, use a [`DecodingReader`] as `R`;
+/// see [`ConfigFile::open_with_encoding`].
+pub struct ConfigFile<P: Pattern, R = BufReader<File>> {
     /// The path to the open file.
     pub path: PathBuf,
     /// A [`ConfigParser`] that reads from the file.
-    pub reader: ConfigParser<std::io::BufReader<std::fs::File>, P>,
+    pub reader: ConfigParser<R, P>,
 }
 
 impl<P: Pattern> ConfigFile<P> {
@@ -18,14 +55,91 @@ impl<P: Pattern> ConfigFile<P> {
     ///
     /// If the file cannot be opened, wraps the [`std::io::Error`] in a [`ParseError`].
     pub fn open(path: impl AsRef<Path>) -> Result<ConfigFile<P>, ParseError> {
-        let file = std::fs::File::open(&path).map_err(ParseError::Io)?;
-        let reader = ConfigParser::new_buffered_with_path(file, Some(path.as_ref()))?;
+        Self::open_with_limits(path, Limits::NONE)
+    }
+
+    /// As [`ConfigFile::open`], but stops reading once any of `limits` is exceeded.
+    ///
+    /// Files larger than [`Limits::max_file_size`] are rejected without being read.
+    /// See [`ConfigParser::new_with_limits`].
+    pub fn open_with_limits(
+        path: impl AsRef<Path>,
+        limits: Limits,
+    ) -> Result<ConfigFile<P>, ParseError> {
+        Self::open_in_context(path.as_ref(), Encoding::Utf8, limits).map_err(|(e, _)| e)
+    }
+}
+
+impl<P: Pattern> ConfigFile<P, DecodingReader<BufReader<File>>> {
+    /// As [`ConfigFile::open`], but decodes the file as UTF-16 if it starts with a byte order mark
+    /// and as `encoding` otherwise. See [`DecodingReader`].
+    ///
+    /// Issues found while decoding can be retrieved with [`ConfigParser::get_ref`]
+    /// and [`DecodingReader::issues`].
+    pub fn open_with_encoding(
+        path: impl AsRef<Path>,
+        encoding: Encoding,
+    ) -> Result<Self, ParseError> {
+        Self::open_with_encoding_and_limits(path, encoding, Limits::NONE)
+    }
+
+    /// As [`ConfigFile::open_with_encoding`], but stops reading once any of `limits` is exceeded.
+    ///
+    /// See [`ConfigFile::open_with_limits`].
+    pub fn open_with_encoding_and_limits(
+        path: impl AsRef<Path>,
+        encoding: Encoding,
+        limits: Limits,
+    ) -> Result<Self, ParseError> {
+        Self::open_in_context(path.as_ref(), encoding, limits).map_err(|(e, _)| e)
+    }
+}
+
+impl<P: Pattern, R: FileReader> ConfigFile<P, R> {
+    /// Opens a file with limits, decoding it as `encoding` if `R` decodes text,
+    /// and returns where an error occurred.
+    ///
+    /// Errors while reading the preamble are on a line of the file;
+    /// other errors, such as failing to open it, refer to the whole file.
+    fn open_in_context(
+        path: &Path,
+        encoding: Encoding,
+        limits: Limits,
+    ) -> Result<Self, (ParseError, Source)> {
+        let in_file = |e| (e, Source::for_file(path));
+        let file = File::open(path).map_err(|e| in_file(ParseError::Io(e)))?;
+        if let Some(max) = limits.max_file_size {
+            let len = file
+                .metadata()
+                .map_err(|e| in_file(ParseError::Io(e)))?
+                .len();
+            if usize::try_from(len).map_or(true, |len| len > max) {
+                return Err(in_file(ParseError::LimitExceeded(LimitExceeded::FileSize(
+                    max,
+                ))));
+            }
+        }
+        let source = R::from_file(BufReader::new(file), encoding);
+        let reader = ConfigParser::new_with_limits_at(source, limits)
+            .map_err(|(e, line)| (e, Source::new(path, line)))?
+            .with_path(Some(path));
         Ok(ConfigFile {
-            path: path.as_ref().to_owned(),
+            path: path.to_owned(),
             reader,
         })
     }
 
+    /// As [`ConfigFile::open_in_context`],
+    /// but wraps errors in an [`Error::Parse`] with where they occurred.
+    pub(crate) fn open_with_context(
+        path: &Path,
+        encoding: Encoding,
+        limits: Limits,
+    ) -> Result<Self, Error> {
+        Self::open_in_context(path, encoding, limits)
+            .map_err(|(e, source)| Error::Parse(e, Some(source)))
+    }
+
     /// Wraps a [`ParseError`] in an [`Error::Parse`]
     /// with the
     ///
@@ -36,16 +150,16 @@ impl<P: Pattern> ConfigFile<P> {
     }
 }
 
-impl<P: Pattern> Iterator for ConfigFile<P> {
+impl<P: Pattern, R: FileReader> Iterator for ConfigFile<P, R> {
     type Item = Result<Section<P>, ParseError>;
     fn next(&mut self) -> Option<Self::Item> {
         self.reader.next()
     }
 }
 
-impl<P: Pattern> std::iter::FusedIterator for ConfigFile<P> {}
+impl<P: Pattern, R: FileReader> std::iter::FusedIterator for ConfigFile<P, R> {}
 
-impl<P: Pattern> PropertiesSource for &mut ConfigFile<P> {
+impl<P: Pattern, R: FileReader> PropertiesSource for &mut ConfigFile<P, R> {
     /// Uses [`ConfigFile::path`] when determining applicability to stop `**` from going too far.
     /// Returns parse errors wrapped in an [`Error::Parse`].
     fn apply_to(
@@ -75,7 +189,19 @@ impl<P: Pattern> PropertiesSource for &mut ConfigFile<P> {
 /// When iterated over, either by using it as an [`Iterator`]
 /// or by calling [`ConfigFiles::iter`], returns [`ConfigFile`]s in the order that they would apply
 /// to a [`Properties`][crate::Properties] map.
-pub struct ConfigFiles<P: Pattern>(Vec<ConfigFile<P>>);
+///
+/// Depending on the [`SymlinkPolicy`], some files may need to be matched against
+/// the real path of the target file; see [`ConfigFiles::real_path`].
+///
+/// As with [`ConfigFile`], files are read as UTF-8 unless `R` is a [`DecodingReader`].
+pub struct ConfigFiles<P: Pattern, R = BufReader<File>> {
+    /// The files, in the reverse of the order they apply in.
+    files: Vec<ConfigFile<P, R>>,
+    /// The real path of the target file, if it was resolved.
+    real_path: Option<PathBuf>,
+    /// The number of files at the start of `files` that apply to `real_path`.
+    real_count: usize,
+}
 
 impl<P: Pattern> ConfigFiles<P> {
     /// Searches for EditorConfig files that might apply to a file at the specified path.
@@ -88,66 +214,227 @@ impl<P: Pattern> ConfigFiles<P> {
     /// If `config_name` is an absolute path, uses the EditorConfig file at that path.
     /// If it's relative, joins it onto every ancestor of `target_path`
     /// and looks for config files at those paths.
-    #[allow(clippy::needless_pass_by_value)]
+    /// Files that exist but cannot be opened are skipped.
+    /// To report them as errors instead, to stop the search before the root of the filesystem,
+    /// or to resolve symbolic links, use [`ConfigFiles::open_with_options`].
     pub fn open(
         target_path: impl AsRef<Path>,
         config_name: Option<impl AsRef<Path>>,
     ) -> Result<Self, Error> {
-        let filename = config_name
-            .as_ref()
-            .map_or_else(|| ".editorconfig".as_ref(), |f| f.as_ref());
-        Ok(ConfigFiles(if filename.is_relative() {
-            let path = target_path.as_ref();
-            let abs_path = if path.is_absolute() {
-                std::borrow::Cow::Borrowed(path)
-            } else {
-                std::borrow::Cow::Owned(std::path::absolute(path).map_err(Error::InvalidCwd)?)
-            };
-            let mut path: &Path = &abs_path;
-            let mut vec = Vec::new();
-            while let Some(dir) = path.parent() {
-                if let Ok(file) = ConfigFile::open(dir.join(filename)) {
-                    let should_break = file.reader.preamble.is_root();
-                    vec.push(file);
-                    if should_break {
-                        break;
+        Self::open_with_limits(target_path, config_name, Limits::NONE)
+    }
+
+    /// As [`ConfigFiles::open`], but stops once any of `limits` is exceeded.
+    ///
+    /// Returns an error without opening any files if `target_path`
+    /// has more than [`Limits::max_depth`] ancestor directories.
+    /// Unlike other errors while opening EditorConfig files,
+    /// exceeding a limit while reading a file's preamble is returned as an error
+    /// rather than the file being skipped.
+    /// Limits on the contents of each file are checked as they are read.
+    pub fn open_with_limits(
+        target_path: impl AsRef<Path>,
+        config_name: Option<impl AsRef<Path>>,
+        limits: Limits,
+    ) -> Result<Self, Error> {
+        let options = SearchOptions::new().with_limits(limits);
+        Self::open_with_options(target_path, config_name, &options)
+    }
+
+    /// As [`ConfigFiles::open`], but looks for every one of `config_names` in each directory.
+    ///
+    /// See [`SearchOptions::with_config_names`] for how the files are ordered.
+    pub fn open_with_names(
+        target_path: impl AsRef<Path>,
+        config_names: impl IntoIterator<Item = impl Into<PathBuf>>,
+    ) -> Result<Self, Error> {
+        let options = SearchOptions::new().with_config_names(config_names);
+        Self::open_with_options(target_path, Option::<&Path>::None, &options)
+    }
+}
+
+impl<P: Pattern, R: FileReader> ConfigFiles<P, R> {
+    /// As [`ConfigFiles::open`], but searches according to `options`.
+    ///
+    /// If `config_name` is `None`, uses [`SearchOptions::config_names`].
+    /// The options for where to stop searching have no effect
+    /// if the only config name is an absolute path.
+    /// It is an error for there to be an absolute path among several config names.
+    /// [`SearchOptions::encoding`] is only used if `R` is a [`DecodingReader`].
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn open_with_options(
+        target_path: impl AsRef<Path>,
+        config_name: Option<impl AsRef<Path>>,
+        options: &SearchOptions,
+    ) -> Result<Self, Error> {
+        let limits = options.limits();
+        let filenames: Vec<&Path> = match &config_name {
+            Some(name) => vec![name.as_ref()],
+            None => options.config_names().collect(),
+        };
+        let path = target_path.as_ref();
+        let abs_path = if path.is_absolute() {
+            std::borrow::Cow::Borrowed(path)
+        } else {
+            std::borrow::Cow::Owned(std::path::absolute(path).map_err(Error::InvalidCwd)?)
+        };
+        let policy = options.symlink_policy();
+        let real_path = (policy != SymlinkPolicy::Lexical).then(|| resolve(&abs_path));
+        let mut files = ConfigFiles {
+            files: Vec::new(),
+            real_count: 0,
+            real_path,
+        };
+        match filenames[..] {
+            [filename] if filename.is_absolute() => {
+                let file = ConfigFile::open_with_context(filename, options.encoding(), limits)?;
+                files.files.push(file);
+                files.real_count = usize::from(files.real_path.is_some());
+            }
+            _ => {
+                if let Some(filename) = filenames.iter().find(|name| name.is_absolute()) {
+                    let error = std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "absolute path among several config names",
+                    );
+                    let source = Source::for_file(filename);
+                    return Err(Error::Parse(ParseError::Io(error), Some(source)));
+                }
+                if let Some(real_path) = &files.real_path {
+                    files.files = Self::search(real_path, &filenames, options, true)?;
+                    files.real_count = files.files.len();
+                }
+                if policy != SymlinkPolicy::Canonical {
+                    let mut lexical = Self::search(&abs_path, &filenames, options, false)?;
+                    if !files.files.is_empty() {
+                        // Skip files that already apply to the real path.
+                        let real_files: Vec<_> = (files.files.iter())
+                            .filter_map(|file| std::fs::canonicalize(&file.path).ok())
+                            .collect();
+                        lexical.retain(|file| {
+                            std::fs::canonicalize(&file.path)
+                                .map_or(true, |path| !real_files.contains(&path))
+                        });
                     }
+                    files.files.append(&mut lexical);
                 }
-                path = dir;
             }
-            vec
-        } else {
-            // TODO: Better errors.
-            vec![ConfigFile::open(filename).map_err(|e| Error::Parse(e, None))?]
-        }))
+        }
+        Ok(files.with_version(options.version()))
+    }
+
+    /// Opens the files named `filenames` in each ancestor directory of `abs_path`,
+    /// innermost first.
+    ///
+    /// If `canonical` is `true`, `abs_path` is canonical,
+    /// and so are the directories to stop at.
+    fn search(
+        abs_path: &Path,
+        filenames: &[&Path],
+        options: &SearchOptions,
+        canonical: bool,
+    ) -> Result<Vec<ConfigFile<P, R>>, Error> {
+        let limits = options.limits();
+        crate::limits::check(
+            limits.max_depth,
+            abs_path.ancestors().skip(1).count(),
+            LimitExceeded::Depth,
+        )
+        .map_err(|e| Error::Parse(ParseError::LimitExceeded(e), None))?;
+        let stop_dirs = options.stop_dirs(canonical).map_err(Error::InvalidCwd)?;
+        let mut path: &Path = abs_path;
+        let mut vec = Vec::new();
+        while let Some(dir) = path.parent() {
+            let mut is_root = false;
+            // Files are stored in the reverse of the order they apply in.
+            for filename in filenames.iter().rev() {
+                let config_path = dir.join(filename);
+                match ConfigFile::open_with_context(&config_path, options.encoding(), limits) {
+                    Ok(file) => {
+                        is_root |= file.reader.preamble.is_root();
+                        vec.push(file);
+                    }
+                    Err(Error::Parse(ParseError::Io(e), _))
+                        if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e @ Error::Parse(ParseError::LimitExceeded(_), _)) => return Err(e),
+                    Err(e) if options.error_policy() == ErrorPolicy::Strict => return Err(e),
+                    Err(_) => (),
+                }
+            }
+            if is_root && !options.ignores_root() || options.is_boundary(dir, &stop_dirs) {
+                break;
+            }
+            path = dir;
+        }
+        Ok(vec)
+    }
+
+    /// Returns `self` with every file set to be parsed as the provided version of EditorConfig.
+    ///
+    /// See [`ConfigParser::with_version`].
+    #[must_use]
+    pub fn with_version(mut self, version: crate::version::SpecVersion) -> Self {
+        for file in &mut self.files {
+            file.reader.version = version;
+        }
+        self
     }
 
     /// Returns an iterator over the contained [`ConfigFiles`].
-    pub fn iter(&self) -> impl Iterator<Item = &ConfigFile<P>> {
-        self.0.iter().rev()
+    pub fn iter(&self) -> impl Iterator<Item = &ConfigFile<P, R>> {
+        self.files.iter().rev()
+    }
+
+    /// Returns the real path of the target file if it was resolved
+    /// according to the [`SymlinkPolicy`].
+    ///
+    /// [`PropertiesSource::apply_to`] matches sections against this path
+    /// in the files that were found by searching from it,
+    /// which are the last files to apply.
+    /// When iterating over the files instead, the last [`ConfigFiles::real_file_count`]
+    /// files should be applied to this path.
+    #[must_use]
+    pub fn real_path(&self) -> Option<&Path> {
+        self.real_path.as_deref()
+    }
+
+    /// Returns the number of files that apply to [`ConfigFiles::real_path`].
+    #[must_use]
+    pub fn real_file_count(&self) -> usize {
+        self.real_count
     }
 
     // To maintain the invariant that these files have not had any sections read,
     // there is no `iter_mut` method.
 }
 
-impl<P: Pattern> Iterator for ConfigFiles<P> {
-    type Item = ConfigFile<P>;
-    fn next(&mut self) -> Option<ConfigFile<P>> {
-        self.0.pop()
+impl<P: Pattern, R: FileReader> Iterator for ConfigFiles<P, R> {
+    type Item = ConfigFile<P, R>;
+    fn next(&mut self) -> Option<ConfigFile<P, R>> {
+        let file = self.files.pop();
+        self.real_count = self.real_count.min(self.files.len());
+        file
     }
 }
 
-impl<P: Pattern> std::iter::FusedIterator for ConfigFiles<P> {}
+impl<P: Pattern, R: FileReader> std::iter::FusedIterator for ConfigFiles<P, R> {}
 
-impl<P: Pattern> PropertiesSource for ConfigFiles<P> {
+impl<P: Pattern, R: FileReader> PropertiesSource for ConfigFiles<P, R> {
+    /// Matches sections against [`ConfigFiles::real_path`] in the files that apply to it.
     fn apply_to(
-        self,
+        mut self,
         props: &mut (impl PropertiesSink + ?Sized),
         path: impl AsRef<Path>,
     ) -> Result<(), crate::Error> {
         let path = path.as_ref();
-        for mut file in self {
+        let real_path = self.real_path.take();
+        let real_path = real_path.as_deref().unwrap_or(path);
+        while let Some(mut file) = self.files.pop() {
+            let path = if self.files.len() < self.real_count {
+                real_path
+            } else {
+                path
+            };
             file.apply_to(props, path)?;
         }
         Ok(())
@@ -2,33 +2,224 @@
 //!
 //! If the `ec4rs_glob` feature is enabled,
 //! this module also includes a re-export of `ec4rs_glob`.
+//! If the `globset` feature is enabled,
+//! this module also includes [`to_globset`] for translating section headers
+//! into `globset` patterns.
+
+#[cfg(all(test, feature = "globset"))]
+mod tests;
+#[cfg(feature = "globset")]
+mod translate;
+
+#[cfg(feature = "globset")]
+pub use translate::{to_globset, TranslateError};
 
 /// A parsed glob pattern which can be used for matching paths.
 ///
 /// This is intended to expose only the subset of functionality relevant for parsing
 /// EditorConfig files, and therefore does not include any way to configure a builder
 /// for the glob pattern.
+///
+/// Paths can be matched either as [`Path`][std::path::Path]s
+/// or as `/`-separated strings.
+/// Each of [`Pattern::matches`] and [`Pattern::matches_str`] is implemented
+/// in terms of the other by default, so implementors must override at least one of them.
+/// With the `no_std` feature, only `matches_str` exists and it must be implemented.
 pub trait Pattern {
     /// The type of error returned by a failed parse.
+    #[cfg(not(feature = "no_std"))]
     type Error: std::error::Error + Sync + Send + 'static;
+    /// The type of error returned by a failed parse.
+    #[cfg(feature = "no_std")]
+    type Error: core::fmt::Debug + core::fmt::Display + Sync + Send + 'static;
     /// Attempts to parse `Self` out of a string.
     fn parse(pattern: &str) -> Result<Self, Self::Error>
     where
         Self: Sized;
+    /// Attempts to parse `Self` out of a string
+    /// as it would have been parsed under the provided version of EditorConfig.
+    ///
+    /// By default, this calls [`Pattern::parse`],
+    /// as is appropriate for engines that don't emulate older versions.
+    fn parse_with_version(
+        pattern: &str,
+        version: crate::version::SpecVersion,
+    ) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        let _ = version;
+        Self::parse(pattern)
+    }
     /// Returns `true` if the provided path matches `Self`.
     ///
     /// If evaluation errors, such as due to depth limits being reached,
     /// this function must return `false`.
+    ///
+    /// By default, this joins the path's components with `/`
+    /// and calls [`Pattern::matches_str`],
+    /// returning `false` for paths that are not valid UTF-8.
+    #[cfg(not(feature = "no_std"))]
+    #[must_use]
+    fn matches(&self, path: &std::path::Path) -> bool {
+        use std::path::Component;
+        let mut joined = alloc::string::String::new();
+        // Whether a `/` is needed before the next name.
+        let mut sep = false;
+        for component in path.components() {
+            let name = match component {
+                Component::Normal(name) => match name.to_str() {
+                    Some(name) => name,
+                    None => return false,
+                },
+                Component::ParentDir => "..",
+                Component::Prefix(_) | Component::RootDir | Component::CurDir => {
+                    sep = true;
+                    continue;
+                }
+            };
+            if sep {
+                joined.push('/');
+            }
+            joined.push_str(name);
+            sep = true;
+        }
+        self.matches_str(&joined)
+    }
+    /// Returns `true` if the provided `/`-separated path matches `Self`.
+    ///
+    /// As with [`Path::components`][std::path::Path::components],
+    /// a leading `/` or `.` marks the start of the path,
+    /// and empty and `.` components are otherwise ignored.
+    ///
+    /// If evaluation errors, such as due to depth limits being reached,
+    /// this function must return `false`.
+    ///
+    /// By default, this calls [`Pattern::matches`].
+    #[cfg(not(feature = "no_std"))]
     #[must_use]
-    fn matches(&self, path: &std::path::Path) -> bool;
+    fn matches_str(&self, path: &str) -> bool {
+        self.matches(std::path::Path::new(path))
+    }
+    /// Returns `true` if the provided `/`-separated path matches `Self`.
+    ///
+    /// A leading `/` or `.` marks the start of the path,
+    /// and empty and `.` components are otherwise ignored.
+    ///
+    /// If evaluation errors, such as due to depth limits being reached,
+    /// this function must return `false`.
+    #[cfg(feature = "no_std")]
+    #[must_use]
+    fn matches_str(&self, path: &str) -> bool;
+    /// Returns `Ok(true)` if the provided path matches `Self`,
+    /// or an error if evaluation was aborted.
+    ///
+    /// Unlike [`Pattern::matches`], this distinguishes a path that does not match
+    /// from one that could not be matched, such as due to a step limit being reached.
+    ///
+    /// By default, this calls [`Pattern::matches`] and never fails.
+    #[cfg(not(feature = "no_std"))]
+    fn try_matches(&self, path: &std::path::Path) -> Result<bool, MatchError> {
+        Ok(self.matches(path))
+    }
+    /// Returns `false` if no path under the directory `dir` can match `Self`.
+    ///
+    /// As with [`Pattern::matches`], `dir` is relative to
+    /// the directory containing the EditorConfig file.
+    /// This is a hint for pruning directory walks, so returning `true`
+    /// for a directory that contains no matches is allowed.
+    ///
+    /// By default, this always returns `true`.
+    #[cfg(not(feature = "no_std"))]
+    #[must_use]
+    fn may_match_under(&self, dir: &std::path::Path) -> bool {
+        let _ = dir;
+        true
+    }
+}
+
+/// Errors that abort matching a path against a [`Pattern`].
+#[cfg(not(feature = "no_std"))]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MatchError {
+    /// The pattern gave up before finding out whether the path matches.
+    Limit,
+    /// Another error specific to the implementation of [`Pattern`].
+    Other(alloc::boxed::Box<dyn std::error::Error + Send + Sync>),
+}
+
+#[cfg(not(feature = "no_std"))]
+impl core::fmt::Display for MatchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MatchError::Limit => write!(f, "matching limit exceeded"),
+            MatchError::Other(e) => write!(f, "{e}"),
+        }
+    }
 }
 
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for MatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MatchError::Limit => None,
+            MatchError::Other(e) => Some(e.as_ref()),
+        }
+    }
+}
+
+/// The most steps [`Glob`] may take backtracking before [`Pattern::try_matches`] gives up.
+#[cfg(all(feature = "ec4rs_glob", not(feature = "no_std")))]
+const GLOB_STEP_LIMIT: usize = 1 << 20;
+
 #[cfg(feature = "ec4rs_glob")]
 pub use ec4rs_glob::*;
 
+/// Escapes the braces of every numeric range in `pattern`, e.g. `{1..3}`,
+/// so that they match themselves.
+#[cfg(feature = "ec4rs_glob")]
+fn escape_numeric_ranges(pattern: &str) -> alloc::string::String {
+    let is_bound = |bound: &str| {
+        let digits = bound.strip_prefix('-').unwrap_or(bound);
+        !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+    };
+    let mut out = alloc::string::String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(idx) = rest.find(['\\', '{']) {
+        out.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        if let Some(escaped) = rest.strip_prefix('\\') {
+            // Keep escapes, including escaped braces, as they are.
+            let len = escaped.chars().next().map_or(0, char::len_utf8);
+            out.push('\\');
+            out.push_str(&escaped[..len]);
+            rest = &escaped[len..];
+            continue;
+        }
+        let body = &rest[1..];
+        let range = body.find('}').map(|end| &body[..end]).filter(|range| {
+            range
+                .split_once("..")
+                .is_some_and(|(lower, upper)| is_bound(lower) && is_bound(upper))
+        });
+        if let Some(range) = range {
+            out.push_str("\\{");
+            out.push_str(range);
+            out.push_str("\\}");
+            rest = &body[range.len() + 1..];
+        } else {
+            out.push('{');
+            rest = body;
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
 #[cfg(feature = "ec4rs_glob")]
 impl Pattern for Glob {
-    type Error = std::convert::Infallible;
+    type Error = core::convert::Infallible;
 
     fn parse(pattern: &str) -> Result<Self, Self::Error>
     where
@@ -38,9 +229,40 @@ impl Pattern for Glob {
         Ok(Glob::new(pattern))
     }
 
+    /// Parses numeric ranges as literal text for versions before 0.11.0.
+    /// See [`SpecVersion::has_numeric_ranges`][crate::version::SpecVersion::has_numeric_ranges].
+    fn parse_with_version(
+        pattern: &str,
+        version: crate::version::SpecVersion,
+    ) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        if version.has_numeric_ranges() {
+            Self::parse(pattern)
+        } else {
+            Self::parse(&escape_numeric_ranges(pattern))
+        }
+    }
+
+    #[cfg(not(feature = "no_std"))]
     fn matches(&self, path: &std::path::Path) -> bool {
         self.matches(path)
     }
+
+    fn matches_str(&self, path: &str) -> bool {
+        self.matches_str(path)
+    }
+
+    #[cfg(not(feature = "no_std"))]
+    fn try_matches(&self, path: &std::path::Path) -> Result<bool, MatchError> {
+        Glob::try_matches(self, path, GLOB_STEP_LIMIT).map_err(|_| MatchError::Limit)
+    }
+
+    #[cfg(not(feature = "no_std"))]
+    fn may_match_under(&self, dir: &std::path::Path) -> bool {
+        Glob::may_match_under(self, dir)
+    }
 }
 
 #[cfg(feature = "globset")]
@@ -51,11 +273,43 @@ impl Pattern for globset::GlobMatcher {
     where
         Self: Sized,
     {
-        let glob = globset::Glob::new(pattern)?;
-        Ok(glob.compile_matcher())
+        match to_globset(pattern) {
+            Ok(glob) => Ok(glob.compile_matcher()),
+            Err(TranslateError::Globset(e)) => Err(e),
+            // Fall back to `globset`'s own reading of the pattern.
+            Err(TranslateError::Unsupported) => Ok(globset::Glob::new(pattern)?.compile_matcher()),
+        }
     }
 
     fn matches(&self, path: &std::path::Path) -> bool {
-        self.is_match(path)
+        use std::path::Component;
+        // Patterns are relative to the directory containing the EditorConfig file.
+        let relative: std::path::PathBuf = path
+            .components()
+            .filter(|c| matches!(c, Component::Normal(_) | Component::ParentDir))
+            .collect();
+        self.is_match(relative)
+    }
+
+    fn may_match_under(&self, dir: &std::path::Path) -> bool {
+        use std::path::Component;
+        let pattern = self.glob().glob();
+        let literal = pattern
+            .find(['*', '?', '[', '{', '\\'])
+            .map_or(pattern, |end| &pattern[..end]);
+        let mut dir_slash = String::new();
+        for component in dir.components() {
+            let name = match component {
+                Component::Normal(name) => match name.to_str() {
+                    Some(name) => name,
+                    None => return true,
+                },
+                Component::ParentDir => "..",
+                Component::Prefix(_) | Component::RootDir | Component::CurDir => continue,
+            };
+            dir_slash.push_str(name);
+            dir_slash.push('/');
+        }
+        dir_slash.starts_with(literal) || literal.starts_with(&dir_slash)
     }
 }
@@ -0,0 +1,155 @@
+use super::Pattern;
+
+/// Checks that `globset` agrees with `ec4rs_glob` on every path.
+fn agrees(pattern: &str, paths: &[&str]) {
+    let expected = ec4rs_glob::Glob::new(pattern);
+    let actual = <globset::GlobMatcher as Pattern>::parse(pattern).unwrap_or_else(|e| {
+        panic!(
+            "`{}` translated to invalid pattern `{}`: {}",
+            pattern,
+            super::translate::translate(pattern).unwrap(),
+            e
+        )
+    });
+    for path in paths {
+        assert_eq!(
+            Pattern::matches(&actual, path.as_ref()),
+            expected.matches(path),
+            "engines disagree on `{}` for `{}` (translated to `{}`)",
+            path,
+            pattern,
+            super::translate::translate(pattern).unwrap()
+        );
+    }
+}
+
+#[test]
+fn translate() {
+    let translate = |pattern| super::translate::translate(pattern).unwrap();
+    assert_eq!(translate("*.rs"), "**/*.rs");
+    assert_eq!(translate("/src/*.rs"), "src/*.rs");
+    assert_eq!(translate("src/"), "src/*");
+    assert_eq!(translate("a/**/b"), "a/**/b");
+    assert_eq!(translate("a**b"), "**/a{*,*/**/*}b");
+    assert_eq!(translate("[!a]"), "**/[!a/]");
+    assert_eq!(
+        translate("{3..120}"),
+        "**/{[3-9],[1-9][0-9],1[0-1][0-9],120}"
+    );
+    assert_eq!(translate("{a,b{c,d}}"), "**/{a,b{c,d}}");
+    assert_eq!(translate("{single}"), "**/\\{single\\}");
+}
+
+#[test]
+fn wildcards() {
+    let paths = [
+        "a.c", "/a.c", "b/a.c", "/b/a.c", "a/b/c.c", "ab/c.c", "a/z.c", "a/b/z.c", "az.c", "abz.c",
+        "b/z.c", ".c", "z.c", "a/b", "a/x/b",
+    ];
+    let patterns = [
+        "*", "*.c", "a*.c", "?.c", "a/*.c", "a/**/z.c", "a**z.c", "b/**z.c", "**/z.c", "a/**",
+        "*/z.c", "**", "a?z.c", "**.c", "/a.c", "a/*/b", "a//b", "*/**/b", "a/{x,}/b", "a/**/",
+        "**/", "a***z.c", "a*?*z.c",
+    ];
+    for pattern in patterns {
+        agrees(pattern, &paths);
+    }
+}
+
+#[test]
+fn charclasses() {
+    let paths = [
+        "a", "b", "-", "]", "!", "^", "/a", "a/b", "ab", "[ab]", "a[b/]c", "a/c",
+    ];
+    let patterns = [
+        "[ab]", "[!ab]", "[a-c]", "[-a]", "[a\\-c]", "[\\]a]", "[!\\]]", "[!^]", "[\\!^]",
+        "a[b/]c", "[[-\\]^]", "[",
+    ];
+    for pattern in patterns {
+        agrees(pattern, &paths);
+    }
+}
+
+#[test]
+fn alternations() {
+    let paths = [
+        "a", "b", "ab", "c", "a,b", "{a,b}", "{a}", "x/c", "a.rs", "a.toml", "bd",
+    ];
+    let patterns = [
+        "{a,b}",
+        "{a}",
+        "{a,b",
+        "a,b}",
+        "{a,{b,c}}",
+        "{,a}b",
+        "*.{rs,toml}",
+        "{a,b{c,d}}",
+        "{a/b,x/c}",
+        "{}",
+    ];
+    for pattern in patterns {
+        agrees(pattern, &paths);
+    }
+}
+
+#[test]
+fn ranges() {
+    let paths = [
+        "0",
+        "1",
+        "3",
+        "5",
+        "15",
+        "60",
+        "120",
+        "121",
+        "060",
+        "5a",
+        "-1",
+        "-0",
+        "-5",
+        "-05",
+        "00",
+        "05",
+        "007",
+        "99",
+        "100",
+        "file-5",
+        "file5",
+        "12345678901234567890",
+    ];
+    let patterns = [
+        "{3..120}",
+        "{1..3}",
+        "{-5..5}",
+        "{-5..-1}",
+        "{00..99}",
+        "{-05..5}",
+        "file-{1..10}",
+        "{1..99999999999999999999999}",
+        "{0..0}",
+        "{a,{1..3}}",
+    ];
+    for pattern in patterns {
+        agrees(pattern, &paths);
+    }
+}
+
+#[test]
+fn may_match_under() {
+    let pattern = <globset::GlobMatcher as Pattern>::parse("src/**/*.rs").unwrap();
+    for dir in ["", "/", "src", "/src/a", "./src/a/b"] {
+        assert!(pattern.may_match_under(dir.as_ref()), "`{dir}` was pruned");
+    }
+    for dir in ["tests", "srcs", "lib/src"] {
+        assert!(
+            !pattern.may_match_under(dir.as_ref()),
+            "`{dir}` wasn't pruned"
+        );
+    }
+    let pattern = <globset::GlobMatcher as Pattern>::parse("*.rs").unwrap();
+    assert!(pattern.may_match_under("any/where".as_ref()));
+    let pattern = <globset::GlobMatcher as Pattern>::parse("/docs/api{1..3}").unwrap();
+    assert!(pattern.may_match_under("docs".as_ref()));
+    assert!(!pattern.may_match_under("doc".as_ref()));
+}
@@ -0,0 +1,377 @@
+//! Translation of EditorConfig section headers into `globset` patterns.
+//!
+//! Headers are first parsed by `ec4rs_glob`, and the parts of the parsed pattern
+//! are translated one at a time, so that both engines agree on what a header means.
+
+use ec4rs_glob::{Part, Parts};
+
+/// One part of a pattern, as in [`Part`], with every alternative collected.
+enum Token<'a> {
+    Literal(&'a str),
+    Sep,
+    /// `*`, any sequence of characters except `/`.
+    Star,
+    /// `**`, any sequence of characters.
+    AnyPath,
+    AnyChar,
+    /// Contains whether the class is negated and its inclusive ranges of characters.
+    Class(bool, Vec<(char, char)>),
+    /// Contains the bounds and width as in [`Part::Range`].
+    Range(&'a str, &'a str, Option<usize>),
+    Alt(Vec<Vec<Token<'a>>>),
+}
+
+/// Collects the parts of a pattern into tokens, leaving out [`Part::Start`].
+///
+/// Returns `None` if there is a part that can't be translated.
+fn tokens(parts: Parts<'_>) -> Option<Vec<Token<'_>>> {
+    parts
+        .filter(|part| !matches!(part, Part::Start))
+        .map(|part| {
+            Some(match part {
+                Part::Separator => Token::Sep,
+                Part::Literal(s) => Token::Literal(s),
+                Part::AnyChar => Token::AnyChar,
+                Part::AnySeq => Token::Star,
+                Part::AnyPath => Token::AnyPath,
+                Part::CharClass(chars, negated) => Token::Class(negated, class_ranges(chars)),
+                Part::Range(lower, upper, width) => Token::Range(lower, upper, width),
+                Part::Alternation(options) => {
+                    Token::Alt(options.map(tokens).collect::<Option<_>>()?)
+                }
+                _ => return None,
+            })
+        })
+        .collect()
+}
+
+/// Collapses sorted characters into inclusive ranges of consecutive characters.
+fn class_ranges(chars: &[char]) -> Vec<(char, char)> {
+    let mut ranges: Vec<(char, char)> = Vec::new();
+    for &c in chars {
+        match ranges.last_mut() {
+            Some((_, last)) if u32::from(*last) + 1 == u32::from(c) => *last = c,
+            _ => ranges.push((c, c)),
+        }
+    }
+    ranges
+}
+
+/// Pushes a pattern for each string of digits between `low` and `high`,
+/// which have the same length, prefixed with `prefix`.
+fn digits(prefix: String, low: &str, high: &str, out: &mut Vec<String>) {
+    let (Some(&first), Some(&last)) = (low.as_bytes().first(), high.as_bytes().first()) else {
+        return out.push(prefix);
+    };
+    let (low, high) = (&low[1..], &high[1..]);
+    if first == last {
+        return digits(format!("{prefix}{}", char::from(first)), low, high, out);
+    }
+    // Digits after the first are unconstrained unless they're at the edges of the range.
+    let mut middle = (first + 1, last - 1);
+    if low.bytes().all(|b| b == b'0') {
+        middle.0 = first;
+    } else {
+        let nines = "9".repeat(low.len());
+        digits(format!("{prefix}{}", char::from(first)), low, &nines, out);
+    }
+    let high_edge = !high.bytes().all(|b| b == b'9');
+    if !high_edge {
+        middle.1 = last;
+    }
+    let any = "[0-9]".repeat(low.len());
+    let (from, to) = (char::from(middle.0), char::from(middle.1));
+    match middle.1.checked_sub(middle.0) {
+        Some(0) => out.push(format!("{prefix}{from}{any}")),
+        Some(_) => out.push(format!("{prefix}[{from}-{to}]{any}")),
+        None => (),
+    }
+    if high_edge {
+        let zeros = "0".repeat(high.len());
+        digits(format!("{prefix}{}", char::from(last)), &zeros, high, out);
+    }
+}
+
+/// Pushes patterns for the numbers from `low` to `high`, which have no leading zeros,
+/// zero-padded to `width` digits if provided.
+fn magnitudes(prefix: &str, low: &str, high: &str, width: Option<usize>, out: &mut Vec<String>) {
+    if let Some(width) = width {
+        let pad = |digits: &str| format!("{digits:0>width$}");
+        return digits(prefix.to_owned(), &pad(low), &pad(high), out);
+    }
+    for len in low.len()..=high.len() {
+        let first = if len == low.len() {
+            low.to_owned()
+        } else {
+            format!("1{}", "0".repeat(len - 1))
+        };
+        let last = if len == high.len() {
+            high.to_owned()
+        } else {
+            "9".repeat(len)
+        };
+        digits(prefix.to_owned(), &first, &last, out);
+    }
+}
+
+/// Splits a bound of a range into whether it's negative and its digits.
+fn split_sign(bound: &str) -> (bool, &str) {
+    match bound.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, bound),
+    }
+}
+
+/// Returns patterns that together match the numbers in a range, given as in [`Part::Range`].
+fn range(lower: &str, upper: &str, width: Option<usize>) -> Vec<String> {
+    let (lower_neg, lower) = split_sign(lower);
+    let (upper_neg, upper) = split_sign(upper);
+    let mut out = Vec::new();
+    if !upper_neg {
+        let low = if lower_neg { "0" } else { lower };
+        magnitudes("", low, upper, width, &mut out);
+    }
+    if lower_neg {
+        // Zero is never written with a `-`.
+        let low = if upper_neg { upper } else { "1" };
+        magnitudes("-", low, lower, width, &mut out);
+    }
+    out
+}
+
+/// Characters that are special inside of a `globset` character class
+/// at the start or end of a range.
+fn is_class_special(c: char) -> bool {
+    matches!(c, ']' | '-' | '!' | '^')
+}
+
+fn write_class(negated: bool, ranges: &[(char, char)], out: &mut String) {
+    let mut ranges = ranges.to_vec();
+    if negated {
+        // Character classes never match the path separator.
+        ranges.push(('/', '/'));
+    }
+    // Special characters are all ASCII, and none are at the ends of it.
+    let step = |c: char, up: bool| {
+        u8::try_from(c)
+            .ok()
+            .map(|b| char::from(if up { b + 1 } else { b - 1 }))
+    };
+    let mut specials = Vec::new();
+    let mut plain = Vec::new();
+    for (mut lo, mut hi) in ranges {
+        while lo <= hi && is_class_special(lo) {
+            specials.push(lo);
+            let Some(next) = step(lo, true) else { break };
+            lo = next;
+        }
+        while lo <= hi && is_class_special(hi) {
+            specials.push(hi);
+            let Some(prev) = step(hi, false) else { break };
+            hi = prev;
+        }
+        if lo <= hi {
+            plain.push((lo, hi));
+        }
+    }
+    specials.sort_unstable();
+    specials.dedup();
+    if !negated && plain.is_empty() && specials.iter().all(|&c| c == '!' || c == '^') {
+        // `globset` would read these as a negated class.
+        out.push('{');
+        for (idx, c) in specials.into_iter().enumerate() {
+            if idx != 0 {
+                out.push(',');
+            }
+            out.push(c);
+        }
+        out.push('}');
+        return;
+    }
+    out.push('[');
+    if negated {
+        out.push('!');
+    }
+    // `]` is literal at the start of a class and `-` is literal at the start or end.
+    if specials.contains(&']') {
+        out.push(']');
+    }
+    let leading_dash = specials.contains(&'-') && plain.is_empty() && !specials.contains(&']');
+    if leading_dash {
+        out.push('-');
+    }
+    for (lo, hi) in plain {
+        out.push(lo);
+        if lo != hi {
+            out.push('-');
+            out.push(hi);
+        }
+    }
+    out.extend(specials.iter().filter(|&&c| c == '!' || c == '^'));
+    if specials.contains(&'-') && !leading_dash {
+        out.push('-');
+    }
+    out.push(']');
+}
+
+/// Returns `true` if the token can match an empty string.
+fn nullable(token: &Token<'_>) -> bool {
+    match token {
+        Token::Star | Token::AnyPath => true,
+        Token::Alt(options) => options.iter().any(|option| option.iter().all(nullable)),
+        _ => false,
+    }
+}
+
+/// Writes a pattern outside of any alternation one path component at a time.
+fn write_components(tokens: &[Token<'_>], out: &mut String) {
+    // The engine treats runs of separators as one.
+    let components: Vec<&[Token<'_>]> = tokens
+        .split(|token| matches!(token, Token::Sep))
+        .filter(|component| !component.is_empty())
+        .collect();
+    for (idx, component) in components.iter().enumerate() {
+        let is_last = idx + 1 == components.len();
+        let after_sep = out.is_empty() || out.ends_with('/');
+        if after_sep && matches!(component, [Token::AnyPath]) {
+            // `globset` only allows `**` as a whole path component.
+            out.push_str("**");
+        } else if !is_last && component.iter().all(nullable) {
+            // The engine lets a `/` match the start of a path component,
+            // so e.g. `a/*/b` matches `a/b`.
+            out.push('{');
+            write_seq(component, (true, true), out);
+            out.push_str("/,}");
+            continue;
+        } else {
+            write_seq(component, (true, !is_last), out);
+        }
+        if !is_last {
+            out.push('/');
+        }
+    }
+}
+
+/// Writes a sequence of tokens.
+///
+/// `seps` contains whether the sequence is directly preceded and followed by a `/`.
+fn write_seq(tokens: &[Token<'_>], seps: (bool, bool), out: &mut String) {
+    for (idx, token) in tokens.iter().enumerate() {
+        let sep_before = match idx.checked_sub(1) {
+            Some(prev) => matches!(tokens[prev], Token::Sep),
+            None => seps.0,
+        };
+        let sep_after = match tokens.get(idx + 1) {
+            Some(next) => matches!(next, Token::Sep),
+            None => seps.1,
+        };
+        match token {
+            Token::Literal(s) => {
+                for c in s.chars() {
+                    if matches!(c, '\\' | '*' | '?' | '[' | ']' | '{' | '}' | ',') {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+            }
+            // The engine treats runs of separators as one.
+            Token::Sep if sep_before || (sep_after && idx + 1 == tokens.len()) => (),
+            Token::Sep => out.push('/'),
+            Token::Star => out.push('*'),
+            Token::AnyChar => out.push('?'),
+            // Any string without a `/`, or one with a `/` in it.
+            Token::AnyPath => out.push_str("{*,*/**/*}"),
+            Token::Class(negated, ranges) => write_class(*negated, ranges, out),
+            Token::Range(lower, upper, width) => {
+                let options = range(lower, upper, *width);
+                if let [option] = options.as_slice() {
+                    out.push_str(option);
+                } else {
+                    out.push('{');
+                    out.push_str(&options.join(","));
+                    out.push('}');
+                }
+            }
+            Token::Alt(options) => {
+                out.push('{');
+                for (idx, option) in options.iter().enumerate() {
+                    if idx != 0 {
+                        out.push(',');
+                    }
+                    write_seq(option, (sep_before, sep_after), out);
+                }
+                out.push('}');
+            }
+        }
+    }
+}
+
+/// Translates an EditorConfig section header into an equivalent `globset` pattern.
+///
+/// The result assumes that backslash escapes, empty alternatives,
+/// and [`literal_separator`][globset::GlobBuilder::literal_separator] are enabled.
+/// Returns `None` if the header contains syntax that this translation doesn't know of.
+pub fn translate(pattern: &str) -> Option<String> {
+    let glob = ec4rs_glob::Glob::new(pattern);
+    let anchored = matches!(glob.parts().next(), Some(Part::Start));
+    let tokens = tokens(glob.parts())?;
+    let mut out = String::new();
+    if !anchored {
+        // Patterns without a `/` match files in any directory.
+        if matches!(tokens.as_slice(), [Token::AnyPath]) {
+            return Some("**".to_owned());
+        }
+        out.push_str("**/");
+    }
+    write_components(&tokens, &mut out);
+    Some(out)
+}
+
+/// Errors from [`to_globset`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TranslateError {
+    /// The section header contains syntax that can't be translated into a `globset` pattern.
+    Unsupported,
+    /// `globset` rejected the translated pattern.
+    Globset(globset::Error),
+}
+
+impl core::fmt::Display for TranslateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TranslateError::Unsupported => write!(f, "cannot translate pattern for globset"),
+            TranslateError::Globset(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for TranslateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TranslateError::Unsupported => None,
+            TranslateError::Globset(e) => Some(e),
+        }
+    }
+}
+
+/// Builds a [`globset::Glob`] that matches the same paths as an EditorConfig section header.
+///
+/// Paths matched against the result must be relative to the directory containing
+/// the EditorConfig file, without a leading `/`.
+/// Numeric ranges are expanded into alternations of character classes,
+/// so matching them does not require post-filtering.
+///
+/// The result matches the same paths as [`ec4rs_glob::Glob`] except when
+/// an alternative begins or ends with a `/` next to a wildcard that can match nothing,
+/// e.g. `*{/a,b}`, and except that `globset` matches `?` and character classes
+/// against one byte of a non-ASCII character at a time.
+pub fn to_globset(pattern: &str) -> Result<globset::Glob, TranslateError> {
+    let translated = translate(pattern).ok_or(TranslateError::Unsupported)?;
+    globset::GlobBuilder::new(&translated)
+        .literal_separator(true)
+        .backslash_escape(true)
+        .empty_alternates(true)
+        .build()
+        .map_err(TranslateError::Globset)
+}
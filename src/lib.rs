@@ -18,29 +18,63 @@
 #![allow(clippy::let_underscore_untyped)] // reason = "Too pedantic."
 #![allow(clippy::missing_errors_doc)] // reason = "Too verbose in moste cases."
 #![cfg_attr(doc_unstable, feature(doc_auto_cfg))]
+#![cfg_attr(all(feature = "no_std", not(test)), no_std)]
 
+#[cfg(all(feature = "no_std", any(feature = "globset", feature = "track-source")))]
+compile_error!("the `globset` and `track-source` features need the standard library");
+
+extern crate alloc;
+
+mod borrowed;
 pub mod cache;
+#[cfg(not(feature = "no_std"))]
+mod decode;
 mod error;
+mod event;
 mod fallback;
+#[cfg(not(feature = "no_std"))]
 mod file;
 pub mod glob;
+pub mod limits;
 mod linereader;
 mod parser;
 pub mod preamble;
 mod properties;
 pub mod property;
+#[cfg(not(feature = "no_std"))]
+mod query;
+#[cfg(not(feature = "no_std"))]
+mod search;
 mod section;
+#[cfg(not(feature = "no_std"))]
+mod stack;
 pub mod string;
 #[cfg(test)]
 mod tests;
 mod traits;
 pub mod version;
 
-pub use error::{Error, ParseError};
-pub use file::{ConfigFile, ConfigFiles};
+pub use borrowed::{BorrowedPairs, BorrowedParser, BorrowedSection};
+#[cfg(not(feature = "no_std"))]
+pub use decode::{DecodeIssue, DecodeIssueKind, DecodingReader, Encoding};
+#[cfg(not(feature = "no_std"))]
+pub use error::Error;
+pub use error::ParseError;
+pub use event::{Event, EventReader};
+#[cfg(not(feature = "no_std"))]
+pub use file::{ConfigFile, ConfigFiles, FileReader};
+pub use linereader::{LineSource, PairSpans, Span, TextReader};
 pub use parser::ConfigParser;
-pub use properties::{Properties, PropertiesSink, PropertiesSource};
+#[cfg(not(feature = "no_std"))]
+pub use properties::PropertiesSource;
+pub use properties::{Properties, PropertiesSink};
+#[cfg(not(feature = "no_std"))]
+pub use query::{Fallbacks, Query};
+#[cfg(not(feature = "no_std"))]
+pub use search::{ErrorPolicy, SearchOptions, SymlinkPolicy};
 pub use section::Section;
+#[cfg(not(feature = "no_std"))]
+pub use stack::{ConfigStack, Layer, LayeredProperties};
 pub use traits::*;
 
 /// Retrieves the [`Properties`] for a file at the given path.
@@ -53,6 +87,9 @@ pub use traits::*;
 ///
 /// EditorConfig files are assumed to be named `.editorconfig`.
 /// If not, use [`properties_from_config_of`].
+/// To combine them with other configuration, use [`ConfigStack`].
+/// For more options, use [`Query`].
+#[cfg(not(feature = "no_std"))]
 #[inline]
 pub fn properties_of<P: crate::glob::Pattern>(
     target_path: impl AsRef<std::path::Path>,
@@ -71,6 +108,8 @@ pub fn properties_of<P: crate::glob::Pattern>(
 /// If `config_name` is an absolute path, uses the EditorConfig file at that path.
 /// If it's relative, joins it onto every ancestor of `target_path`
 /// and looks for config files at those paths.
+/// To look for more than one name in each directory, use [`ConfigFiles::open_with_names`].
+#[cfg(not(feature = "no_std"))]
 #[inline]
 pub fn properties_from_config_of<P: crate::glob::Pattern>(
     target_path: impl AsRef<std::path::Path>,
@@ -0,0 +1,166 @@
+//! Limits on the resources used to read EditorConfig files.
+//!
+//! By default, nothing is limited.
+//! Programs that process untrusted files may want to limit the size of what they read
+//! using [`ConfigParser::new_with_limits`][crate::ConfigParser::new_with_limits]
+//! or, without the `no_std` feature, `ConfigFiles::open_with_limits`.
+
+#[cfg(test)]
+mod tests;
+
+/// Configurable limits on what may be read. `None` means unlimited.
+///
+/// ```
+/// # #[cfg(feature = "ec4rs_glob")] {
+/// use ec4rs::limits::{LimitExceeded, Limits};
+/// use ec4rs::{ConfigParser, ParseError, TextReader};
+/// let limits = Limits::default().with_max_line_len(16);
+/// let text = "[*]\nindent_style = space\n";
+/// let mut parser = ConfigParser::<_, ec4rs::glob::Glob>::new_with_limits(
+///     TextReader::new(text),
+///     limits,
+/// ).unwrap();
+/// let error = parser.read_section().err().unwrap();
+/// assert!(matches!(error, ParseError::LimitExceeded(LimitExceeded::LineLength(16))));
+/// assert_eq!(parser.line_no(), 2);
+/// # }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub struct Limits {
+    /// The maximum number of bytes of text in a file.
+    pub max_file_size: Option<usize>,
+    /// The maximum number of bytes in a line, not counting its line terminator.
+    pub max_line_len: Option<usize>,
+    /// The maximum number of sections in a file.
+    pub max_sections: Option<usize>,
+    /// The maximum number of key-value pairs in one section or in the preamble.
+    pub max_pairs_per_section: Option<usize>,
+    /// The maximum number of ancestor directories to search for EditorConfig files.
+    pub max_depth: Option<usize>,
+}
+
+impl Limits {
+    /// No limits at all. This is the default.
+    pub const NONE: Limits = Limits {
+        max_file_size: None,
+        max_line_len: None,
+        max_sections: None,
+        max_pairs_per_section: None,
+        max_depth: None,
+    };
+
+    /// Returns `self` with [`Limits::max_file_size`] set to `max`.
+    #[must_use]
+    pub fn with_max_file_size(mut self, max: usize) -> Self {
+        self.max_file_size = Some(max);
+        self
+    }
+
+    /// Returns `self` with [`Limits::max_line_len`] set to `max`.
+    #[must_use]
+    pub fn with_max_line_len(mut self, max: usize) -> Self {
+        self.max_line_len = Some(max);
+        self
+    }
+
+    /// Returns `self` with [`Limits::max_sections`] set to `max`.
+    #[must_use]
+    pub fn with_max_sections(mut self, max: usize) -> Self {
+        self.max_sections = Some(max);
+        self
+    }
+
+    /// Returns `self` with [`Limits::max_pairs_per_section`] set to `max`.
+    #[must_use]
+    pub fn with_max_pairs_per_section(mut self, max: usize) -> Self {
+        self.max_pairs_per_section = Some(max);
+        self
+    }
+
+    /// Returns `self` with [`Limits::max_depth`] set to `max`.
+    #[must_use]
+    pub fn with_max_depth(mut self, max: usize) -> Self {
+        self.max_depth = Some(max);
+        self
+    }
+}
+
+/// The limit that was exceeded, with its maximum.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum LimitExceeded {
+    /// A file was larger than [`Limits::max_file_size`].
+    FileSize(usize),
+    /// A line was longer than [`Limits::max_line_len`].
+    LineLength(usize),
+    /// A file had more than [`Limits::max_sections`] sections.
+    Sections(usize),
+    /// A section had more than [`Limits::max_pairs_per_section`] key-value pairs.
+    PairsPerSection(usize),
+    /// A path had more than [`Limits::max_depth`] ancestor directories.
+    Depth(usize),
+}
+
+impl core::fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LimitExceeded::FileSize(max) => write!(f, "file is larger than {max} bytes"),
+            LimitExceeded::LineLength(max) => write!(f, "line is longer than {max} bytes"),
+            LimitExceeded::Sections(max) => write!(f, "more than {max} sections"),
+            LimitExceeded::PairsPerSection(max) => {
+                write!(f, "more than {max} key-value pairs in a section")
+            }
+            LimitExceeded::Depth(max) => write!(f, "more than {max} ancestor directories"),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for LimitExceeded {}
+
+/// Returns an error made by `error` if `value` is greater than `max`.
+pub(crate) fn check(
+    max: Option<usize>,
+    value: usize,
+    error: fn(usize) -> LimitExceeded,
+) -> Result<(), LimitExceeded> {
+    match max {
+        Some(max) if value > max => Err(error(max)),
+        _ => Ok(()),
+    }
+}
+
+/// Returns an error if `line`, less any line terminator, is longer than `max` bytes.
+pub(crate) fn check_line(line: &[u8], max: usize) -> Result<(), crate::ParseError> {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    check(Some(max), line.len(), LimitExceeded::LineLength)
+        .map_err(crate::ParseError::LimitExceeded)
+}
+
+/// Counts the sections and key-value pairs read so far.
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct Counts {
+    sections: usize,
+    pairs: usize,
+}
+
+impl Counts {
+    /// Counts one section header, checking it against `limits`.
+    pub fn section(&mut self, limits: &Limits) -> Result<(), LimitExceeded> {
+        self.sections += 1;
+        self.pairs = 0;
+        check(limits.max_sections, self.sections, LimitExceeded::Sections)
+    }
+
+    /// Counts one key-value pair in the current section, checking it against `limits`.
+    pub fn pair(&mut self, limits: &Limits) -> Result<(), LimitExceeded> {
+        self.pairs += 1;
+        check(
+            limits.max_pairs_per_section,
+            self.pairs,
+            LimitExceeded::PairsPerSection,
+        )
+    }
+}
@@ -0,0 +1,134 @@
+use crate::event::EventReader;
+use crate::limits::{LimitExceeded, Limits};
+use crate::{LineSource, ParseError, TextReader};
+
+/// Reads every event, returning the line number and limit of the first error.
+fn first_error(source: impl LineSource, limits: Limits) -> Option<(usize, LimitExceeded)> {
+    let mut reader = EventReader::new(source).with_limits(limits);
+    loop {
+        match reader.next_event() {
+            Ok(_) => (),
+            Err(ParseError::Eof) => return None,
+            Err(ParseError::LimitExceeded(limit)) => {
+                let line_no = reader.line_no();
+                // Nothing more is read after a limit is exceeded.
+                assert!(matches!(
+                    reader.next_event(),
+                    Err(ParseError::LimitExceeded(l)) if l == limit
+                ));
+                return Some((line_no, limit));
+            }
+            Err(e) => panic!("unexpected error {e}"),
+        }
+    }
+}
+
+/// Checks the first error when reading `text` as a string and, without `no_std`, as bytes.
+fn assert_first_error(text: &str, limits: Limits, expected: Option<(usize, LimitExceeded)>) {
+    assert_eq!(first_error(TextReader::new(text), limits), expected);
+    #[cfg(not(feature = "no_std"))]
+    assert_eq!(first_error(text.as_bytes(), limits), expected);
+}
+
+#[test]
+fn line_length() {
+    let limits = Limits::default().with_max_line_len(5);
+    for text in ["[abc]\r\nk = v\n", "[abc]\nk=v", "\n\n"] {
+        assert_first_error(text, limits, None);
+    }
+    let expected = Some((2, LimitExceeded::LineLength(5)));
+    for text in ["[abc]\nk = vv\n", "[abc]\r\n[abcd]", "[abc]\n      \r\n"] {
+        assert_first_error(text, limits, expected);
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+#[test]
+fn line_length_is_bounded() {
+    use std::io::BufRead;
+    let text = format!("[a]\n{}\n[b]\n", "x".repeat(100));
+    let mut source = text.as_bytes();
+    let mut buf = String::new();
+    LineSource::read_line_limited(&mut source, &mut buf, 10).unwrap();
+    let error = LineSource::read_line_limited(&mut source, &mut buf, 10).unwrap_err();
+    assert!(matches!(
+        error,
+        ParseError::LimitExceeded(LimitExceeded::LineLength(10))
+    ));
+    // Only enough of the long line was read to tell that it was too long.
+    assert_eq!(source.fill_buf().unwrap().len(), 100 - 12 + 5);
+}
+
+#[test]
+fn file_size() {
+    let text = "[a]\nk = v\n";
+    let limits = Limits::default().with_max_file_size(text.len());
+    assert_eq!(first_error(TextReader::new(text), limits), None);
+    let limits = Limits::default().with_max_file_size(text.len() - 1);
+    let expected = Some((2, LimitExceeded::FileSize(text.len() - 1)));
+    assert_first_error(text, limits, expected);
+    // A line limit that is higher than what's left of the file doesn't change the error.
+    let limits = limits.with_max_line_len(100);
+    assert_first_error(text, limits, expected);
+    let limits = limits.with_max_line_len(3);
+    let expected = Some((2, LimitExceeded::LineLength(3)));
+    assert_first_error(text, limits, expected);
+}
+
+#[test]
+fn sections_and_pairs() {
+    let text = "a = 1\nb = 2\n[a]\nc = 3\n[b]\nd = 4\ne = 5\n[c]\n";
+    let limits = Limits::default().with_max_sections(3);
+    assert_eq!(first_error(TextReader::new(text), limits), None);
+    let limits = Limits::default().with_max_sections(2);
+    let expected = Some((8, LimitExceeded::Sections(2)));
+    assert_eq!(first_error(TextReader::new(text), limits), expected);
+    let limits = Limits::default().with_max_pairs_per_section(2);
+    assert_eq!(first_error(TextReader::new(text), limits), None);
+    let limits = Limits::default().with_max_pairs_per_section(1);
+    let expected = Some((2, LimitExceeded::PairsPerSection(1)));
+    assert_eq!(first_error(TextReader::new(text), limits), expected);
+    let text = "[a]\nc = 3\n[b]\nd = 4\ne = 5\n";
+    let expected = Some((5, LimitExceeded::PairsPerSection(1)));
+    assert_eq!(first_error(TextReader::new(text), limits), expected);
+}
+
+#[cfg(not(feature = "no_std"))]
+#[test]
+fn decoded_line_length() {
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in "[abc]\r\né = vv\n".encode_utf16() {
+        bytes.extend(unit.to_le_bytes());
+    }
+    let source = || crate::DecodingReader::new(bytes.as_slice());
+    let limits = Limits::default().with_max_line_len(7);
+    assert_eq!(first_error(source(), limits), None);
+    let limits = Limits::default().with_max_line_len(6);
+    let expected = Some((2, LimitExceeded::LineLength(6)));
+    assert_eq!(first_error(source(), limits), expected);
+}
+
+#[cfg(all(not(feature = "no_std"), feature = "ec4rs_glob"))]
+#[test]
+fn depth() {
+    let path = if cfg!(windows) {
+        "C:\\a\\b\\c\\.editorconfig"
+    } else {
+        "/a/b/c/.editorconfig"
+    };
+    let limits = Limits::default();
+    let error = crate::ConfigFiles::<crate::glob::Glob>::open_with_limits(
+        path,
+        Option::<&str>::None,
+        limits.with_max_depth(3),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(error.to_string(), "more than 3 ancestor directories");
+    let files = crate::ConfigFiles::<crate::glob::Glob>::open_with_limits(
+        path,
+        Some("nonexistent.editorconfig"),
+        limits.with_max_depth(4),
+    );
+    assert!(files.unwrap().iter().next().is_none());
+}
@@ -1,18 +1,99 @@
 #[cfg(test)]
 mod tests;
 
+use crate::limits::{self, LimitExceeded, Limits};
 use crate::ParseError;
 
-use std::io;
+use alloc::boxed::Box;
+use alloc::string::String;
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+/// A range of bytes in the text of an EditorConfig file.
+///
+/// This is both a range of byte offsets from the start of the file
+/// and a range of columns, counted in bytes from the start of the line.
+/// Any BOM at the start of a line counts towards its columns.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Span {
+    /// The offset of the start of the line.
+    line_start: usize,
+    start: usize,
+    end: usize,
+}
+
+impl Span {
+    /// Constructs a span of the columns `start..end` of a line at the start of the text.
+    pub(crate) fn new(start: usize, end: usize) -> Span {
+        Span {
+            line_start: 0,
+            start,
+            end,
+        }
+    }
+
+    /// Returns `self` moved to a line starting at offset `line_start`.
+    #[must_use]
+    pub(crate) fn at(self, line_start: usize) -> Span {
+        Span { line_start, ..self }
+    }
+
+    /// Returns the range of byte offsets from the start of the file.
+    #[must_use]
+    pub fn bytes(&self) -> core::ops::Range<usize> {
+        self.line_start + self.start..self.line_start + self.end
+    }
+
+    /// Returns the range of 0-based columns, in bytes from the start of the line.
+    #[must_use]
+    pub fn columns(&self) -> core::ops::Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// The [`Span`]s of the parts of a key-value pair.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct PairSpans {
+    /// The span of the key.
+    pub key: Span,
+    /// The span of the `=`.
+    pub separator: Span,
+    /// The span of the value, which is empty if the value is.
+    pub value: Span,
+}
+
+impl PairSpans {
+    #[must_use]
+    fn at(self, line_start: usize) -> PairSpans {
+        PairSpans {
+            key: self.key.at(line_start),
+            separator: self.separator.at(line_start),
+            value: self.value.at(line_start),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Line<'a> {
-    /// Either a comment or an empty line.
+    /// An empty line.
     Nothing,
-    /// A section header, e.g. `[something.rs]`
-    Section(&'a str),
+    /// A comment, e.g. `# hello`, including its leading `#` or `;`.
+    Comment(&'a str, Span),
+    /// A section header, e.g. `[something.rs]`, and the span of the text between the brackets.
+    Section(&'a str, Span),
     /// A propery/key-value pair, e.g. `indent_size = 2`
-    Pair(&'a str, &'a str),
+    Pair(&'a str, &'a str, PairSpans),
+}
+
+impl Line<'_> {
+    /// Returns `self` with its spans moved to a line starting at offset `line_start`.
+    #[must_use]
+    fn at(self, line_start: usize) -> Self {
+        match self {
+            Line::Nothing => Line::Nothing,
+            Line::Comment(text, span) => Line::Comment(text, span.at(line_start)),
+            Line::Section(header, span) => Line::Section(header, span.at(line_start)),
+            Line::Pair(key, value, spans) => Line::Pair(key, value, spans.at(line_start)),
+        }
+    }
 }
 
 type LineReadResult<'a> = Result<Line<'a>, ParseError>;
@@ -24,10 +105,20 @@ type LineReadResult<'a> = Result<Line<'a>, ParseError>;
 ///
 /// If a line begins with `U+FEFF` (ZWNBSP, more commonly used as the BOM),
 /// this function strips it.
-pub fn parse_line(line: &str) -> LineReadResult<'_> {
-    let line = line.strip_prefix("\u{feff}").unwrap_or(line).trim_start();
-    if line.is_empty() || line.starts_with(is_comment) {
+///
+/// The returned spans are relative to the start of `line`.
+pub fn parse_line(raw: &str) -> LineReadResult<'_> {
+    let line = raw.strip_prefix("\u{feff}").unwrap_or(raw).trim_start();
+    // The column at which `line` starts.
+    let lead = raw.len() - line.len();
+    if line.is_empty() {
         Ok(Line::Nothing)
+    } else if line.starts_with(is_comment) {
+        let comment = line.trim_end();
+        Ok(Line::Comment(
+            comment,
+            Span::new(lead, lead + comment.len()),
+        ))
     } else if line.starts_with('[') {
         let Some(bracket) = line.rfind(']') else {
             return Err(ParseError::InvalidLine);
@@ -47,14 +138,22 @@ pub fn parse_line(line: &str) -> LineReadResult<'_> {
         if s.is_empty() {
             Err(ParseError::InvalidSection(None))
         } else {
-            Ok(Line::Section(s))
+            Ok(Line::Section(s, Span::new(lead + 1, lead + bracket)))
         }
     } else if let Some((key_raw, val_raw)) = line.split_once('=') {
         let key = key_raw.trim_end();
         if key.is_empty() {
             Err(ParseError::InvalidLine)
         } else {
-            Ok(Line::Pair(key, val_raw.trim()))
+            let eq = lead + key_raw.len();
+            let value = val_raw.trim();
+            let value_start = eq + 1 + val_raw.len() - val_raw.trim_start().len();
+            let spans = PairSpans {
+                key: Span::new(lead, lead + key.len()),
+                separator: Span::new(eq, eq + 1),
+                value: Span::new(value_start, value_start + value.len()),
+            };
+            Ok(Line::Pair(key, value, spans))
         }
     } else {
         Err(ParseError::InvalidLine)
@@ -67,8 +166,11 @@ pub fn parse_line(line: &str) -> LineReadResult<'_> {
 /// Does minimal validation and does not modify the input text in any way.
 pub struct LineReader<R> {
     ticker: usize,
+    /// The byte offset of the start of the contained line.
+    offset: usize,
     line: String,
     reader: R,
+    limits: Limits,
 }
 
 impl<R> LineReader<R> {
@@ -76,16 +178,30 @@ impl<R> LineReader<R> {
     pub fn new(r: R) -> LineReader<R> {
         LineReader {
             ticker: 0,
+            offset: 0,
             line: String::with_capacity(256),
             reader: r,
+            limits: Limits::NONE,
         }
     }
 
+    /// Returns `self` set to enforce the line length and file size limits in `limits`.
+    #[must_use]
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Returns the line number of the contained line.
     pub fn line_no(&self) -> usize {
         self.ticker
     }
 
+    /// Returns a reference to the underlying line source.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
     /// Returns a reference to the contained line.
     pub fn line(&self) -> &str {
         self.line.as_str()
@@ -93,28 +209,147 @@ impl<R> LineReader<R> {
 
     /// Parses the contained line using [`parse_line`].
     ///
-    /// It's usually not necessary to call this method.
-    /// See [`LineReader::next_line`].
+    /// Call this after [`LineReader::advance`] to parse the line that was read.
+    /// Unlike with [`parse_line`], the returned spans are relative to the start of the text.
     pub fn reparse(&self) -> LineReadResult<'_> {
-        parse_line(self.line())
+        parse_line(self.line()).map(|line| line.at(self.offset))
     }
 }
 
-impl<R: io::BufRead> LineReader<R> {
-    /// Reads and parses the next line from the stream.
-    pub fn next_line(&mut self) -> LineReadResult<'_> {
+impl<R: LineSource> LineReader<R> {
+    /// Reads the next line from the stream without parsing it.
+    pub fn advance(&mut self) -> Result<(), ParseError> {
+        self.offset += self.line.len();
         self.line.clear();
-        match self.reader.read_line(&mut self.line) {
-            Err(e) => Err(ParseError::Io(e)),
+        let max_file_size = self.limits.max_file_size;
+        // Stop long lines from being read in full if they would exceed either limit.
+        let budget = max_file_size.map(|max| max.saturating_sub(self.offset));
+        let max_len = match (self.limits.max_line_len, budget) {
+            (Some(max), Some(budget)) => Some(max.min(budget)),
+            (max, budget) => max.or(budget),
+        };
+        let result = match max_len {
+            Some(max) => self.reader.read_line_limited(&mut self.line, max),
+            None => self.reader.read_line(&mut self.line),
+        };
+        match result {
+            Err(ParseError::LimitExceeded(LimitExceeded::LineLength(max)))
+                if Some(max) != self.limits.max_line_len =>
+            {
+                self.ticker += 1;
+                // Report the limit that the budget was derived from.
+                let max = max_file_size.unwrap_or(max);
+                Err(ParseError::LimitExceeded(LimitExceeded::FileSize(max)))
+            }
             Ok(0) => Err(ParseError::Eof),
-            Ok(_) => {
+            Err(e) => {
+                // The line that could not be read is the contained line.
+                self.ticker += 1;
+                Err(e)
+            }
+            Ok(len) => {
                 self.ticker += 1;
-                self.reparse()
+                limits::check(max_file_size, self.offset + len, LimitExceeded::FileSize)
+                    .map_err(ParseError::LimitExceeded)
             }
         }
     }
 }
 
+/// Trait for sources of text that can be read one line at a time.
+///
+/// This is implemented for every [`BufRead`][std::io::BufRead] unless the `no_std` feature is enabled,
+/// and for [`TextReader`], which reads text that is already in memory.
+pub trait LineSource {
+    /// Appends the next line, including its line terminator if it has one, to `buf`.
+    ///
+    /// Returns the number of bytes appended, which is `0` if there are no more lines.
+    fn read_line(&mut self, buf: &mut String) -> Result<usize, ParseError>;
+
+    /// As [`LineSource::read_line`], but returns [`LimitExceeded::LineLength`]
+    /// if the line is longer than `max` bytes, not counting its line terminator.
+    ///
+    /// Implementations should avoid reading much more than `max` bytes of a long line.
+    /// What is left of the line after an error is unspecified.
+    fn read_line_limited(&mut self, buf: &mut String, max: usize) -> Result<usize, ParseError> {
+        let start = buf.len();
+        let len = self.read_line(buf)?;
+        limits::check_line(&buf.as_bytes()[start..], max)?;
+        Ok(len)
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl<R: std::io::BufRead> LineSource for R {
+    fn read_line(&mut self, buf: &mut String) -> Result<usize, ParseError> {
+        std::io::BufRead::read_line(self, buf).map_err(ParseError::Io)
+    }
+
+    fn read_line_limited(&mut self, buf: &mut String, max: usize) -> Result<usize, ParseError> {
+        use std::io::{BufRead, Read};
+        // Enough to tell whether the line is too long even if it ends with `\r\n`.
+        let take = u64::try_from(max).map_or(u64::MAX, |max| max.saturating_add(2));
+        let mut bytes = alloc::vec::Vec::new();
+        self.take(take)
+            .read_until(b'\n', &mut bytes)
+            .map_err(ParseError::Io)?;
+        limits::check_line(&bytes, max)?;
+        let line = core::str::from_utf8(&bytes)
+            .map_err(|e| ParseError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+        buf.push_str(line);
+        Ok(line.len())
+    }
+}
+
+/// A [`LineSource`] over a string slice.
+///
+/// This allows parsing in-memory text with the `no_std` feature.
+///
+/// ```
+/// # #[cfg(feature = "ec4rs_glob")] {
+/// use ec4rs::{ConfigParser, TextReader, glob::Glob};
+/// let text = "root = true\n[*.rs]\nindent_size = 4\n";
+/// let mut parser = ConfigParser::<_, Glob>::new(TextReader::new(text)).unwrap();
+/// assert!(parser.preamble.is_root());
+/// let section = parser.next().unwrap().unwrap();
+/// assert!(section.applies_to_str("src/lib.rs"));
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextReader<'a> {
+    text: &'a str,
+}
+
+impl<'a> TextReader<'a> {
+    /// Constructs a new `TextReader` that reads lines from `text`.
+    #[must_use]
+    pub fn new(text: &'a str) -> Self {
+        TextReader { text }
+    }
+}
+
+impl LineSource for TextReader<'_> {
+    fn read_line(&mut self, buf: &mut String) -> Result<usize, ParseError> {
+        let Some((line, rest)) = split_line(self.text) else {
+            return Ok(0);
+        };
+        buf.push_str(line);
+        self.text = rest;
+        Ok(line.len())
+    }
+}
+
+/// Splits the first line, including its line terminator if it has one, off of `text`.
+///
+/// Returns `None` if `text` is empty.
+pub(crate) fn split_line(text: &str) -> Option<(&str, &str)> {
+    if text.is_empty() {
+        return None;
+    }
+    let len = text.find('\n').map_or(text.len(), |idx| idx + 1);
+    Some(text.split_at(len))
+}
+
 fn is_comment(c: char) -> bool {
     c == ';' || c == '#'
 }
@@ -1,15 +1,35 @@
 use crate::linereader::*;
 use crate::ParseError;
 
-fn test_lines(lines: &[(&'static str, Line<'static>)]) {
+/// A [`Line`] without its spans.
+#[derive(PartialEq, Eq, Debug)]
+enum Parsed<'a> {
+    Nothing,
+    Comment(&'a str),
+    Section(&'a str),
+    Pair(&'a str, &'a str),
+}
+
+impl<'a> From<Line<'a>> for Parsed<'a> {
+    fn from(line: Line<'a>) -> Self {
+        match line {
+            Line::Nothing => Parsed::Nothing,
+            Line::Comment(text, _) => Parsed::Comment(text),
+            Line::Section(header, _) => Parsed::Section(header),
+            Line::Pair(key, value, _) => Parsed::Pair(key, value),
+        }
+    }
+}
+
+fn test_lines(lines: &[(&'static str, Parsed<'static>)]) {
     for (line, expected) in lines {
-        assert_eq!(parse_line(line).unwrap(), *expected)
+        assert_eq!(Parsed::from(parse_line(line).unwrap()), *expected)
     }
 }
 
 #[test]
 fn valid_props() {
-    use Line::Pair;
+    use Parsed::Pair;
     test_lines(&[
         ("foo=bar", Pair("foo", "bar")),
         ("Foo=Bar", Pair("Foo", "Bar")),
@@ -26,7 +46,7 @@ fn valid_props() {
 
 #[test]
 fn valid_sections() {
-    use Line::Section;
+    use Parsed::Section;
     test_lines(&[
         ("[foo]", Section("foo")),
         ("[[foo]]", Section("[foo]")),
@@ -45,17 +65,17 @@ fn valid_sections() {
 
 #[test]
 fn valid_nothing() {
-    use Line::Nothing;
+    use Parsed::{Comment, Nothing};
     test_lines(&[
         ("\t", Nothing),
         ("\r", Nothing),
         ("", Nothing),
         ("   ", Nothing),
-        (";comment", Nothing),
-        ("#comment", Nothing),
-        ("  # comment", Nothing),
-        ("# [section]", Nothing),
-        ("# foo=bar", Nothing),
+        (";comment", Comment(";comment")),
+        ("#comment", Comment("#comment")),
+        ("  # comment\r\n", Comment("# comment")),
+        ("# [section]", Comment("# [section]")),
+        ("# foo=bar", Comment("# foo=bar")),
     ])
 }
 
@@ -99,3 +119,58 @@ fn invalid_sections() {
         assert_eq!(&*e, expected, "mismatch in expected error data");
     }
 }
+
+#[test]
+fn text_reader() {
+    fn next_line<R: LineSource>(reader: &mut LineReader<R>) -> Result<Parsed<'_>, ParseError> {
+        reader.advance()?;
+        reader.reparse().map(Parsed::from)
+    }
+    let mut reader = LineReader::new(TextReader::new("[*]\r\nfoo = bar\n\n; end"));
+    assert_eq!(next_line(&mut reader).unwrap(), Parsed::Section("*"));
+    assert_eq!(reader.line(), "[*]\r\n");
+    assert_eq!(next_line(&mut reader).unwrap(), Parsed::Pair("foo", "bar"));
+    assert_eq!(next_line(&mut reader).unwrap(), Parsed::Nothing);
+    assert_eq!(next_line(&mut reader).unwrap(), Parsed::Comment("; end"));
+    assert_eq!(reader.line(), "; end");
+    assert_eq!(reader.line_no(), 4);
+    assert!(matches!(next_line(&mut reader), Err(ParseError::Eof)));
+    assert!(matches!(
+        next_line(&mut LineReader::new(TextReader::new(""))),
+        Err(ParseError::Eof)
+    ));
+}
+
+#[test]
+fn spans() {
+    let columns = |span: Span| span.columns();
+    let Line::Pair(_, _, spans) = parse_line("\u{feff} Foo =  bar baz \n").unwrap() else {
+        panic!("expected a pair");
+    };
+    assert_eq!(columns(spans.key), 4..7);
+    assert_eq!(columns(spans.separator), 8..9);
+    assert_eq!(columns(spans.value), 11..18);
+    let Line::Pair(_, _, spans) = parse_line("k=").unwrap() else {
+        panic!("expected a pair");
+    };
+    assert_eq!(columns(spans.value), 2..2);
+    assert!(matches!(
+        parse_line("  [a=b] # c").unwrap(),
+        Line::Section("a=b", span) if columns(span) == (3..6)
+    ));
+    assert!(matches!(
+        parse_line(" ; c \r\n").unwrap(),
+        Line::Comment("; c", span) if columns(span) == (1..4)
+    ));
+
+    let mut reader = LineReader::new(TextReader::new("[*]\r\n\nk = v\n"));
+    for _ in 0..3 {
+        reader.advance().unwrap();
+    }
+    let Line::Pair(_, _, spans) = reader.reparse().unwrap() else {
+        panic!("expected a pair");
+    };
+    assert_eq!(spans.key.bytes(), 6..7);
+    assert_eq!(spans.value.bytes(), 10..11);
+    assert_eq!(columns(spans.value), 4..5);
+}
@@ -1,37 +1,47 @@
-#[cfg(feature = "ec4rs_glob")]
+#[cfg(all(feature = "ec4rs_glob", not(feature = "no_std")))]
 #[cfg(test)]
 mod tests;
 
 use crate::cache::Cache;
 use crate::cache::CommonKeyCache;
 use crate::cache::CommonValueCache;
+use crate::event::{Event, EventReader};
 use crate::glob::Pattern;
-use crate::linereader::LineReader;
+use crate::limits::Limits;
+use crate::linereader::LineSource;
 use crate::preamble::Preamble;
+#[cfg(not(feature = "no_std"))]
 use crate::properties::PropertiesSink;
+use crate::version::SpecVersion;
 use crate::ParseError;
 use crate::Section;
+#[cfg(not(feature = "no_std"))]
 use std::io;
+#[cfg(not(feature = "no_std"))]
 use std::path::Path;
 
 /// Parser for the text of an EditorConfig file.
 ///
-/// This struct wraps any [`BufRead`][std::io::BufRead].
+/// This struct wraps any [`LineSource`], such as a [`BufRead`][std::io::BufRead]
+/// or a [`TextReader`][crate::TextReader] over in-memory text.
 /// It eagerly parses the preamble on construction.
-/// [`Section`]s may then be parsed by calling [`ConfigParser::read_section`].
-pub struct ConfigParser<R: io::BufRead, P: Pattern, K = CommonKeyCache, V = CommonValueCache> {
+/// [`Section`]s may then be parsed by calling [`ConfigParser::read_section`],
+/// or the rest of the file may be read line-by-line with [`ConfigParser::next_event`].
+pub struct ConfigParser<R, P: Pattern, K = CommonKeyCache, V = CommonValueCache> {
     /// The preamble for this EditorConfig file.
     pub preamble: Preamble,
     eof: bool,
-    reader: LineReader<R>,
+    reader: EventReader<R>,
     cache_k: K,
     cache_v: V,
+    pub(crate) version: SpecVersion,
     #[allow(clippy::type_complexity)]
-    glob_marker: std::marker::PhantomData<fn() -> Result<P, P::Error>>,
+    glob_marker: core::marker::PhantomData<fn() -> Result<P, P::Error>>,
     #[cfg(feature = "track-source")]
     path: Option<crate::string::Shared<Path>>,
 }
 
+#[cfg(not(feature = "no_std"))]
 impl<R: io::Read, P: Pattern> ConfigParser<io::BufReader<R>, P, CommonKeyCache, CommonValueCache> {
     /// Convenience function for construction using an unbuffered [`io::Read`].
     ///
@@ -51,56 +61,81 @@ impl<R: io::Read, P: Pattern> ConfigParser<io::BufReader<R>, P, CommonKeyCache,
     }
 }
 
-impl<R: io::BufRead, P: Pattern> ConfigParser<R, P, CommonKeyCache, CommonValueCache> {
+impl<R: LineSource, P: Pattern> ConfigParser<R, P, CommonKeyCache, CommonValueCache> {
     /// Constructs a new [`ConfigParser`] and reads the preamble from the provided source,
     /// which is assumed to be a file at `path`.
     ///
     /// Returns `Ok` if the preamble was parsed successfully,
     /// otherwise returns `Err` with the error that occurred during reading.
+    #[cfg(not(feature = "no_std"))]
     pub fn new_with_path(
         buf_source: R,
         #[allow(unused)] path: Option<&(impl AsRef<Path> + ?Sized)>,
     ) -> Result<Self, ParseError> {
-        let mut reader = LineReader::new(buf_source);
-        let mut is_root = false;
+        Ok(Self::new(buf_source)?.with_path(path))
+    }
+    /// Returns `self` set to attribute values to the file at `path`.
+    #[cfg(not(feature = "no_std"))]
+    #[allow(unused_mut)]
+    pub(crate) fn with_path(
+        mut self,
+        #[allow(unused)] path: Option<&(impl AsRef<Path> + ?Sized)>,
+    ) -> Self {
+        #[cfg(feature = "track-source")]
+        {
+            self.path = path.map(|p| crate::string::Shared::from(p.as_ref()));
+        }
+        self
+    }
+    /// Constructs a new [`ConfigParser`] and reads the preamble from the provided source.
+    ///
+    /// Returns `Ok` if the preamble was parsed successfully,
+    /// otherwise returns `Err` with the error that occurred during reading.
+    pub fn new(buf_source: R) -> Result<Self, ParseError> {
+        Self::new_with_limits(buf_source, Limits::NONE)
+    }
+    /// Constructs a new [`ConfigParser`] that stops reading once any of `limits` is exceeded,
+    /// and reads the preamble from the provided source.
+    ///
+    /// Exceeding a limit results in a [`ParseError::LimitExceeded`],
+    /// including while reading the preamble. See [`EventReader::with_limits`].
+    pub fn new_with_limits(buf_source: R, limits: Limits) -> Result<Self, ParseError> {
+        Self::new_with_limits_at(buf_source, limits).map_err(|(e, _)| e)
+    }
+    /// As [`ConfigParser::new_with_limits`],
+    /// but also returns the number of the line that an error occurred on.
+    pub(crate) fn new_with_limits_at(
+        buf_source: R,
+        limits: Limits,
+    ) -> Result<Self, (ParseError, usize)> {
+        let mut reader = EventReader::new(buf_source).with_limits(limits);
+        let mut preamble = Preamble::new();
         let eof = loop {
-            use crate::linereader::Line;
-            match reader.next_line() {
+            match reader.next_event() {
                 Err(ParseError::Eof) => break true,
-                Err(e) => return Err(e),
-                Ok(Line::Nothing) => (),
-                Ok(Line::Section(_)) => break false,
-                Ok(Line::Pair(k, v)) => {
-                    if "root".eq_ignore_ascii_case(k) {
-                        if let Ok(b) = v.to_ascii_lowercase().parse::<bool>() {
-                            is_root = b;
-                        }
-                    }
-                    // Quietly ignore unknown properties.
-                }
+                Err(e) => return Err((e, reader.line_no())),
+                Ok((_, Event::SectionStart(..))) => break false,
+                Ok((line_no, Event::PreamblePair(k, v, _))) => preamble.read_pair(k, v, line_no),
+                Ok(_) => (),
             }
         };
+        // Leave the first section header to be read by `read_section`.
+        reader.unread();
         Ok(ConfigParser {
-            preamble: Preamble::new().with_root(is_root),
+            preamble,
             eof,
             reader,
             cache_k: CommonKeyCache,
             cache_v: CommonValueCache,
-            glob_marker: std::marker::PhantomData,
+            version: SpecVersion::CURRENT,
+            glob_marker: core::marker::PhantomData,
             #[cfg(feature = "track-source")]
-            path: path.map(|p| crate::string::Shared::from(p.as_ref())),
+            path: None,
         })
     }
-    /// Constructs a new [`ConfigParser`] and reads the preamble from the provided source.
-    ///
-    /// Returns `Ok` if the preamble was parsed successfully,
-    /// otherwise returns `Err` with the error that occurred during reading.
-    pub fn new(buf_source: R) -> Result<Self, ParseError> {
-        Self::new_with_path(buf_source, Option::<&Path>::None)
-    }
 }
 
-impl<R: io::BufRead, P: Pattern, K, V> ConfigParser<R, P, K, V> {
+impl<R: LineSource, P: Pattern, K, V> ConfigParser<R, P, K, V> {
     /// Returns `true` if there may be another section to read.
     pub fn has_more(&self) -> bool {
         !self.eof
@@ -111,6 +146,47 @@ impl<R: io::BufRead, P: Pattern, K, V> ConfigParser<R, P, K, V> {
         self.reader.line_no()
     }
 
+    /// Returns a reference to the underlying line source,
+    /// e.g. to get the issues found by a `DecodingReader`.
+    pub fn get_ref(&self) -> &R {
+        self.reader.get_ref()
+    }
+
+    /// Reads the next [`Event`] after the preamble, starting with the first section header.
+    ///
+    /// This can be used to process sections without buffering them,
+    /// and can be freely mixed with calls to [`ConfigParser::read_section`].
+    /// Returns [`ParseError::Eof`] once there are no more lines.
+    pub fn next_event(&mut self) -> Result<(usize, Event<'_>), ParseError>
+    where
+        R: LineSource,
+    {
+        if self.eof {
+            return Err(ParseError::Eof);
+        }
+        let event = self.reader.next_event();
+        if let Err(ParseError::Eof) = event {
+            self.eof = true;
+        }
+        event
+    }
+
+    /// Returns `self` set to parse sections as the provided version of EditorConfig would.
+    ///
+    /// This affects how section headers are parsed, using [`Pattern::parse_with_version`],
+    /// and how values are read. See [`SpecVersion`] for the differences between versions.
+    /// The default is [`SpecVersion::CURRENT`].
+    #[must_use]
+    pub fn with_version(mut self, version: SpecVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Returns the version of EditorConfig that sections are parsed as.
+    pub fn version(&self) -> SpecVersion {
+        self.version
+    }
+
     /// Returns a version of `self` with the provided cache for keys.
     pub fn with_key_cache<C>(self, cache_k: C) -> ConfigParser<R, P, C, V> {
         ConfigParser {
@@ -119,7 +195,8 @@ impl<R: io::BufRead, P: Pattern, K, V> ConfigParser<R, P, K, V> {
             reader: self.reader,
             cache_k,
             cache_v: self.cache_v,
-            glob_marker: std::marker::PhantomData,
+            version: self.version,
+            glob_marker: core::marker::PhantomData,
             #[cfg(feature = "track-source")]
             path: self.path,
         }
@@ -133,56 +210,57 @@ impl<R: io::BufRead, P: Pattern, K, V> ConfigParser<R, P, K, V> {
             reader: self.reader,
             cache_k: self.cache_k,
             cache_v,
-            glob_marker: std::marker::PhantomData,
+            version: self.version,
+            glob_marker: core::marker::PhantomData,
             #[cfg(feature = "track-source")]
             path: self.path,
         }
     }
 }
 
-impl<R: io::BufRead, P: Pattern, K: Cache, V: Cache> ConfigParser<R, P, K, V> {
+impl<R: LineSource, P: Pattern, K: Cache, V: Cache> ConfigParser<R, P, K, V> {
     /// Parses a [`Section`], reading more if needed.
     pub fn read_section(&mut self) -> Result<Section<P>, ParseError> {
-        use crate::linereader::Line;
-        if self.eof {
-            return Err(ParseError::Eof);
-        }
-        if let Ok(Line::Section(header)) = self.reader.reparse() {
-            let mut section = Section::new(header);
-            loop {
-                // Get line_no here to avoid borrowing issues, increment for 1-based indices.
-                #[cfg(feature = "track-source")]
-                let line_no = self.reader.line_no() + 1;
-                match self.reader.next_line() {
-                    Err(e) => {
-                        self.eof = true;
-                        break if matches!(e, ParseError::Eof) {
-                            Ok(section)
-                        } else {
-                            Err(e)
-                        };
-                    }
-                    Ok(Line::Section(_)) => break Ok(section),
-                    Ok(Line::Nothing) => (),
-                    Ok(Line::Pair(k, v)) => {
-                        #[allow(unused_mut)]
-                        let mut v = self.cache_v.get_shared_string(v);
-                        #[cfg(feature = "track-source")]
-                        if let Some(path) = self.path.as_ref() {
-                            use crate::string::Source;
-                            v.set_source(Source::new(path, line_no));
-                        }
-                        section.insert(self.cache_k.get_shared_string(k), v);
+        let version = self.version;
+        let mut section = match self.next_event()? {
+            (_, Event::SectionStart(header, _)) => Section::new_with_version(header, version),
+            _ => return Err(ParseError::InvalidLine),
+        };
+        loop {
+            match self.reader.next_event() {
+                Err(e) => {
+                    self.eof = true;
+                    break if matches!(e, ParseError::Eof) {
+                        Ok(section)
+                    } else {
+                        Err(e)
+                    };
+                }
+                Ok((_, Event::SectionStart(..))) => {
+                    self.reader.unread();
+                    break Ok(section);
+                }
+                #[allow(unused_variables)]
+                Ok((line_no, Event::Pair(k, v, spans))) => {
+                    let Some(v) = self.version.read_value(v) else {
+                        continue;
+                    };
+                    #[allow(unused_mut)]
+                    let mut v = self.cache_v.get_shared_string(v);
+                    #[cfg(feature = "track-source")]
+                    if let Some(path) = self.path.as_ref() {
+                        use crate::string::Source;
+                        v.set_source(Source::new(path, line_no).with_span(spans.value));
                     }
+                    section.insert(self.cache_k.get_shared_string(k), v);
                 }
+                Ok(_) => (),
             }
-        } else {
-            Err(ParseError::InvalidLine)
         }
     }
 }
 
-impl<R: io::BufRead, P: Pattern, K: Cache, V: Cache> Iterator for ConfigParser<R, P, K, V> {
+impl<R: LineSource, P: Pattern, K: Cache, V: Cache> Iterator for ConfigParser<R, P, K, V> {
     type Item = Result<Section<P>, ParseError>;
     fn next(&mut self) -> Option<Self::Item> {
         match self.read_section() {
@@ -193,12 +271,13 @@ impl<R: io::BufRead, P: Pattern, K: Cache, V: Cache> Iterator for ConfigParser<R
     }
 }
 
-impl<R: io::BufRead, P: Pattern, K: Cache, V: Cache> std::iter::FusedIterator
+impl<R: LineSource, P: Pattern, K: Cache, V: Cache> core::iter::FusedIterator
     for ConfigParser<R, P, K, V>
 {
 }
 
-impl<R: io::BufRead, P: Pattern, K: Cache, V: Cache> crate::PropertiesSource
+#[cfg(not(feature = "no_std"))]
+impl<R: LineSource, P: Pattern, K: Cache, V: Cache> crate::PropertiesSource
     for &mut ConfigParser<R, P, K, V>
 {
     fn apply_to(
@@ -207,16 +286,40 @@ impl<R: io::BufRead, P: Pattern, K: Cache, V: Cache> crate::PropertiesSource
         path: impl AsRef<std::path::Path>,
     ) -> Result<(), crate::Error> {
         let path = path.as_ref();
-        // TODO: Don't buffer entire sections.
-        for section_result in self {
-            match section_result {
-                Ok(section) => {
-                    let _ = section.apply_to(props, path);
-                }
+        let parser = self;
+        // Whether the section being read applies to `path`.
+        let mut applies = false;
+        let result = loop {
+            if parser.eof {
+                break Ok(());
+            }
+            match parser.reader.next_event() {
+                Err(ParseError::Eof) => break Ok(()),
                 // TODO: Better errors.
-                Err(error) => return Err(crate::Error::Parse(error, None)),
+                Err(error) => break Err(crate::Error::Parse(error, None)),
+                Ok((_, Event::SectionStart(header, _))) => {
+                    applies = P::parse_with_version(header, parser.version)
+                        .is_ok_and(|pattern| pattern.matches(path));
+                }
+                #[allow(unused_variables)]
+                Ok((line_no, Event::Pair(k, v, spans))) if applies => {
+                    let Some(v) = parser.version.read_value(v) else {
+                        continue;
+                    };
+                    #[allow(unused_mut)]
+                    let mut v = parser.cache_v.get_shared_string(v);
+                    #[cfg(feature = "track-source")]
+                    if let Some(source_path) = parser.path.as_ref() {
+                        use crate::string::Source;
+                        v.set_source(Source::new(source_path, line_no).with_span(spans.value));
+                    }
+                    let k = parser.cache_k.get_shared_string(k).into_lowercase();
+                    props.add_property(k, v);
+                }
+                Ok(_) => (),
             }
-        }
-        Ok(())
+        };
+        parser.eof = true;
+        result
     }
 }
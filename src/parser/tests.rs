@@ -56,6 +56,33 @@ fn prelude_unknown() {
     validate("foo = bar\nroot = true", true, expect![]);
 }
 
+#[test]
+fn prelude_pairs() {
+    let text = "; comment\nRoot = True\n\nIndent_Style = tab\nfoo = bar\n[*]\nindent_size = 2";
+    let parser = ConfigParser::<_, Glob>::new_buffered(text.as_bytes()).unwrap();
+    let pairs: Vec<_> = parser
+        .preamble
+        .pairs()
+        .iter()
+        .map(|pair| (pair.key().as_str(), pair.value().as_str(), pair.line_no()))
+        .collect();
+    assert_eq!(
+        pairs,
+        [
+            ("root", "True", 2),
+            ("indent_style", "tab", 4),
+            ("foo", "bar", 5)
+        ]
+    );
+    let misplaced: Vec<_> = parser
+        .preamble
+        .misplaced_properties()
+        .map(crate::preamble::PreamblePair::line_no)
+        .collect();
+    assert_eq!(misplaced, [4]);
+    assert!(parser.preamble.is_root());
+}
+
 #[test]
 fn sections_empty() {
     validate("[foo]", false, expect![[]]);
@@ -110,3 +137,137 @@ fn bom() {
     validate("\u{feff}root=true", true, expect![]);
     validate("\u{feff}[*]\nfoo=bar", false, expect![[("foo", "bar", 2)]]);
 }
+
+#[test]
+fn events_and_sections() {
+    use crate::event::Event;
+    use crate::{ParseError, TextReader};
+    let text = "root=true\n[a]\nk=v\n; c\n[b]\nk=w\n";
+    let mut parser = ConfigParser::<_, Glob>::new(TextReader::new(text)).unwrap();
+    assert!(parser.preamble.is_root());
+    assert!(matches!(
+        parser.next_event(),
+        Ok((2, Event::SectionStart("a", _)))
+    ));
+    assert!(matches!(
+        parser.next_event(),
+        Ok((3, Event::Pair("k", "v", _)))
+    ));
+    assert!(matches!(
+        parser.next_event(),
+        Ok((4, Event::Comment("; c", _)))
+    ));
+    let section = parser.read_section().unwrap();
+    assert!(section.applies_to("b"));
+    assert_eq!(section.props().get_raw_for_key("k").unwrap().as_str(), "w");
+    assert!(matches!(parser.next_event(), Err(ParseError::Eof)));
+    assert!(!parser.has_more());
+}
+
+#[test]
+fn apply_to_streams() {
+    use crate::string::SharedString;
+    use crate::ParseError;
+    use crate::PropertiesSource;
+    let text =
+        "[*.md]\nindent_size=2\n[*.rs]\nIndent_Size=4\n[*.{rs,toml}]\nfoo=bar\n[*.md]\nfoo=baz";
+    let path = std::path::Path::new(".editorconfig");
+    let mut parser =
+        ConfigParser::<_, Glob>::new_buffered_with_path(text.as_bytes(), Some(path)).unwrap();
+    let mut pairs = Vec::new();
+    let mut sink = |k: SharedString, v: SharedString| {
+        let source = v
+            .source()
+            .map(|src| (src.get().1, src.span().unwrap().bytes()));
+        pairs.push((k.to_string(), v.to_string(), source));
+    };
+    (&mut parser).apply_to(&mut sink, "src/lib.rs").unwrap();
+    let source = |line_no, bytes| cfg!(feature = "track-source").then_some((line_no, bytes));
+    assert_eq!(
+        pairs,
+        [
+            ("indent_size".to_owned(), "4".to_owned(), source(4, 40..41)),
+            ("foo".to_owned(), "bar".to_owned(), source(6, 60..63)),
+        ]
+    );
+    assert!(!parser.has_more());
+
+    let mut parser = ConfigParser::<_, Glob>::new_buffered("[*]\nk=v\nbad\n".as_bytes()).unwrap();
+    let mut props = crate::Properties::new();
+    let error = (&mut parser).apply_to(&mut props, "a").unwrap_err();
+    assert!(matches!(
+        error,
+        crate::Error::Parse(ParseError::InvalidLine, None)
+    ));
+    assert_eq!(props.get_raw_for_key("k").unwrap().as_str(), "v");
+}
+
+#[test]
+fn versions() {
+    use crate::version::SpecVersion;
+    use crate::PropertiesSource;
+    let text = "[*]\nfoo = bar ; baz\nempty =\nindent_style = tab\n";
+    let parse = |version| {
+        let parser = ConfigParser::<_, Glob>::new_buffered(text.as_bytes())
+            .unwrap()
+            .with_version(version);
+        assert_eq!(parser.version(), version);
+        parser
+    };
+    for version in [SpecVersion::new(0, 8, 0), SpecVersion::CURRENT] {
+        let mut streamed = crate::Properties::new();
+        (&mut parse(version)).apply_to(&mut streamed, "a").unwrap();
+        streamed.use_fallbacks_for(version);
+        let mut buffered = parse(version).next().unwrap().unwrap().into_props();
+        buffered.use_fallbacks_for(version);
+        assert_eq!(streamed, buffered);
+        let pairs: Vec<_> = buffered
+            .iter()
+            .map(|(k, v)| format!("{k}={}", v.as_str()))
+            .collect();
+        if version.has_legacy_fallbacks() {
+            assert_eq!(pairs, ["foo=bar", "indent_style=tab"]);
+        } else {
+            assert_eq!(
+                pairs,
+                [
+                    "foo=bar ; baz",
+                    "empty=",
+                    "indent_style=tab",
+                    "indent_size=tab"
+                ]
+            );
+        }
+    }
+}
+
+#[test]
+fn glob_versions() {
+    use crate::glob::Pattern;
+    use crate::version::SpecVersion;
+    let old = SpecVersion::new(0, 10, 0);
+    let cases = [
+        ("{1..3}", "2", true, false),
+        ("{1..3}", "{1..3}", false, true),
+        ("a{-1..1}b", "a0b", true, false),
+        ("a{-1..1}b", "a{-1..1}b", false, true),
+        ("{a,{1..3}}", "2", true, false),
+        ("{a,{1..3}}", "a", true, true),
+        ("{a..b}", "{a..b}", true, true),
+        ("\\{1..3}", "{1..3}", true, true),
+        ("{1..3,a}", "a", true, true),
+    ];
+    for (pattern, path, current, legacy) in cases {
+        let glob = Glob::parse_with_version(pattern, SpecVersion::CURRENT).unwrap();
+        assert_eq!(glob.matches_str(path), current, "{pattern} {path}");
+        let glob = Glob::parse_with_version(pattern, old).unwrap();
+        assert_eq!(glob.matches_str(path), legacy, "{pattern} {path} at {old}");
+    }
+    let text = "[{1..3}]\nk = v\n";
+    let mut parser = ConfigParser::<_, Glob>::new_buffered(text.as_bytes())
+        .unwrap()
+        .with_version(old);
+    let section = parser.next().unwrap().unwrap();
+    assert!(section.applies_to_str("{1..3}"));
+    assert!(!section.applies_to_str("2"));
+}
@@ -9,7 +9,49 @@
 //!
 //! The `Preamble` type exists to cover cases where `ec4rs` may need to improve preamble support
 //! in the future without breaking backward compatibility.
-//! At this time, it only parses and retains the `root` property.
+//! It parses the `root` property and retains every key-value pair as a [`PreamblePair`],
+//! so that tools can warn about editor properties that were placed in the preamble
+//! and therefore have no effect.
+
+use crate::string::SharedString;
+use alloc::vec::Vec;
+
+/// A key-value pair from an EditorConfig preamble.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PreamblePair {
+    key: SharedString,
+    value: SharedString,
+    line_no: usize,
+}
+
+impl PreamblePair {
+    /// Returns the key, lowercased as keys in sections are.
+    #[must_use]
+    pub fn key(&self) -> &SharedString {
+        &self.key
+    }
+
+    /// Returns the value.
+    #[must_use]
+    pub fn value(&self) -> &SharedString {
+        &self.value
+    }
+
+    /// Returns the 1-based number of the line the pair was read from.
+    #[must_use]
+    pub fn line_no(&self) -> usize {
+        self.line_no
+    }
+
+    /// Returns `true` if the key is one of the standard properties for configuring editors,
+    /// which have no effect in the preamble.
+    ///
+    /// See [`lookup_standard_key`][crate::property::lookup_standard_key].
+    #[must_use]
+    pub fn is_editor_property(&self) -> bool {
+        crate::property::lookup_standard_key(&self.key).is_some()
+    }
+}
 
 /// A parsed EditorConfig preamble.
 ///
@@ -17,13 +59,17 @@
 #[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
 pub struct Preamble {
     root: bool,
+    pairs: Vec<PreamblePair>,
 }
 
 impl Preamble {
     /// Returns a new default [`Preamble`].
     #[must_use]
     pub fn new() -> Self {
-        Preamble { root: false }
+        Preamble {
+            root: false,
+            pairs: Vec::new(),
+        }
     }
 
     /// Returns `true` if `self` the `root` property was specified and has a value
@@ -42,4 +88,42 @@ impl Preamble {
         self.root = value;
         self
     }
+
+    /// Returns every key-value pair in the preamble, including `root`,
+    /// in the order they were read.
+    #[must_use]
+    pub fn pairs(&self) -> &[PreamblePair] {
+        &self.pairs
+    }
+
+    /// Returns an iterator over the pairs in the preamble that set editor properties.
+    ///
+    /// EditorConfig ignores these, as properties only apply to files
+    /// when they are in a section.
+    ///
+    /// ```
+    /// use ec4rs::BorrowedParser;
+    /// let parser = BorrowedParser::new("root = true\nindent_style = tab\n[*]\n").unwrap();
+    /// let misplaced: Vec<_> = parser.preamble.misplaced_properties().collect();
+    /// assert_eq!(misplaced.len(), 1);
+    /// assert_eq!(misplaced[0].key().as_str(), "indent_style");
+    /// assert_eq!(misplaced[0].line_no(), 2);
+    /// ```
+    pub fn misplaced_properties(&self) -> impl Iterator<Item = &PreamblePair> {
+        self.pairs.iter().filter(|pair| pair.is_editor_property())
+    }
+
+    /// Updates `self` with a key-value pair read from the preamble on line `line_no`.
+    pub(crate) fn read_pair(&mut self, key: &str, value: &str, line_no: usize) {
+        if "root".eq_ignore_ascii_case(key) {
+            if let Ok(b) = value.to_ascii_lowercase().parse::<bool>() {
+                self.root = b;
+            }
+        }
+        self.pairs.push(PreamblePair {
+            key: SharedString::new(key).into_lowercase(),
+            value: SharedString::new(value),
+            line_no,
+        });
+    }
 }
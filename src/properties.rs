@@ -8,6 +8,7 @@ use crate::{
     string::{SharedString, ToSharedString},
     PropertyKey, PropertyValue,
 };
+use alloc::vec::Vec;
 
 /// Map of key-value pairs ("properties").
 ///
@@ -219,6 +220,15 @@ impl Properties {
     pub fn use_fallbacks_legacy(&mut self) {
         crate::fallback::add_fallbacks(self, true)
     }
+
+    /// Adds fallback values for certain common key-value pairs
+    /// as the provided version of EditorConfig would.
+    ///
+    /// This is [`Properties::use_fallbacks_legacy`] for versions before 0.9.0
+    /// and [`Properties::use_fallbacks`] otherwise.
+    pub fn use_fallbacks_for(&mut self, version: crate::version::SpecVersion) {
+        crate::fallback::add_fallbacks(self, version.has_legacy_fallbacks())
+    }
 }
 
 impl PartialEq for Properties {
@@ -235,8 +245,8 @@ impl PartialEq for Properties {
 
 impl Eq for Properties {}
 
-impl std::fmt::Debug for Properties {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for Properties {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("Properties")
             .field(&self.pairs.as_slice())
             .finish()
@@ -285,6 +295,41 @@ impl<K: ToSharedString, V: ToSharedString> Extend<(K, V)> for Properties {
     }
 }
 
+/// Serializes the properties as a map, in order.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Properties {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+/// Deserializes the properties from a map, keeping its order.
+///
+/// Keys are used as-is, as with [`Properties::insert_raw_for_key`].
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Properties {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Properties;
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("a map of EditorConfig properties")
+            }
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<Properties, A::Error> {
+                let mut props = Properties::new();
+                while let Some((key, val)) = map.next_entry::<SharedString, SharedString>()? {
+                    props.insert_raw_for_key(key, val);
+                }
+                Ok(props)
+            }
+        }
+        deserializer.deserialize_map(Visitor)
+    }
+}
+
 /// Trait for types that can accept EditorConfig key-value pairs ("properties").
 pub trait PropertiesSink {
     /// Adds one key-value pair to `self`.
@@ -322,6 +367,7 @@ impl PropertiesSink for Properties {
 }
 
 /// Trait for types that can add properties to any [`PropertiesSink`].
+#[cfg(not(feature = "no_std"))]
 pub trait PropertiesSource {
     /// Adds properties that apply to a file at the specified path
     /// to the provided [`PropertiesSink`].
@@ -332,6 +378,7 @@ pub trait PropertiesSource {
     ) -> Result<(), crate::Error>;
 }
 
+#[cfg(not(feature = "no_std"))]
 impl<T: IntoIterator<Item = (impl ToSharedString, impl ToSharedString)>> PropertiesSource for T {
     fn apply_to(
         self,
@@ -343,6 +390,7 @@ impl<T: IntoIterator<Item = (impl ToSharedString, impl ToSharedString)>> Propert
     }
 }
 
+#[cfg(not(feature = "no_std"))]
 impl PropertiesSource for &Properties {
     fn apply_to(
         self,
@@ -20,8 +20,8 @@ macro_rules! impls {
                 Some((key, val))
             }
         }
-        impl<'a> std::iter::FusedIterator for $name<'a> {}
-        impl<'a> std::iter::ExactSizeIterator for $name<'a> {
+        impl<'a> core::iter::FusedIterator for $name<'a> {}
+        impl<'a> core::iter::ExactSizeIterator for $name<'a> {
             fn len(&self) -> usize {
                 self.0.len()
             }
@@ -32,11 +32,11 @@ macro_rules! impls {
 
 /// An iterator over [`Properties`].
 #[derive(Clone)]
-pub struct Iter<'a>(pub(super) std::slice::Iter<'a, (SharedString, SharedString)>);
+pub struct Iter<'a>(pub(super) core::slice::Iter<'a, (SharedString, SharedString)>);
 
 impls! {Iter, &'a SharedString}
 
 /// An iterator over [`Properties`] that allows value mutation.
-pub struct IterMut<'a>(pub(super) std::slice::IterMut<'a, (SharedString, SharedString)>);
+pub struct IterMut<'a>(pub(super) core::slice::IterMut<'a, (SharedString, SharedString)>);
 
 impls! {IterMut, &'a mut SharedString}
@@ -1,4 +1,4 @@
-use crate::{string::SharedString, Properties, PropertiesSource};
+use crate::{string::SharedString, Properties};
 
 static BASIC_KEYS: [&str; 4] = ["2", "3", "0", "1"];
 static ALT_VALUES: [&str; 4] = ["a", "b", "c", "d"];
@@ -79,8 +79,10 @@ fn try_insert_replacing() {
     }
 }
 
+#[cfg(not(feature = "no_std"))]
 #[test]
 fn apply_empty_to() {
+    use crate::PropertiesSource;
     let mut props = Properties::new();
     props.insert_raw_for_key("foo", "a");
     props.insert_raw_for_key("bar", "b");
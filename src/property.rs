@@ -14,18 +14,20 @@ pub use language_tag::*;
 use super::{PropertyKey, PropertyValue};
 use crate::string::SharedString;
 
-use std::fmt::Display;
+use alloc::string::ToString;
+use core::fmt::Display;
 
 /// Error for common property parse failures.
 #[derive(Clone, Copy, Debug)]
 pub struct UnknownValueError;
 
 impl Display for UnknownValueError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "unknown value")
     }
 }
 
+#[cfg(not(feature = "no_std"))]
 impl std::error::Error for UnknownValueError {}
 
 // TODO: Deduplicate these macros a bit?
@@ -42,7 +44,7 @@ macro_rules! property_choice {
             $($variant),+
         }
 
-        impl std::str::FromStr for $prop_id {
+        impl core::str::FromStr for $prop_id {
             type Err = UnknownValueError;
             fn from_str(raw: &str) -> Result<Self, Self::Err> {
                 match &*crate::string::into_lowercase(raw) {
@@ -75,7 +77,7 @@ macro_rules! property_choice {
         }
 
         impl Display for $prop_id {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
                 match self {
                     $prop_id::Unset => "unset".fmt(f),
                     $($prop_id::$variant => $string.fmt(f)),*
@@ -101,7 +103,7 @@ macro_rules! property_valued {
             $(,$variant)*
         }
 
-        impl std::str::FromStr for $prop_id {
+        impl core::str::FromStr for $prop_id {
             type Err = UnknownValueError;
             fn from_str(raw: &str) -> Result<Self, Self::Err> {
                 match &*crate::string::into_lowercase(raw) {
@@ -137,7 +139,7 @@ macro_rules! property_valued {
         }
 
         impl Display for $prop_id {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
                 match self {
                     $prop_id::Unset => "unset".fmt(f),
                     $prop_id::Value(v) => v.fmt(f),
@@ -197,7 +199,7 @@ pub enum SpellingLanguage {
     Value(LanguageTag),
 }
 
-impl std::str::FromStr for SpellingLanguage {
+impl core::str::FromStr for SpellingLanguage {
     type Err = UnknownValueError;
     fn from_str(raw: &str) -> Result<Self, Self::Err> {
         if raw.eq_ignore_ascii_case("unset") {
@@ -241,7 +243,7 @@ impl PropertyKey for SpellingLanguage {
 }
 
 impl Display for SpellingLanguage {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
             SpellingLanguage::Unset => crate::string::UNSET.fmt(f),
             SpellingLanguage::Value(v) => v.fmt(f),
@@ -53,7 +53,7 @@ impl LanguageTag {
         #[cfg(not(feature = "bcp_47"))]
         {
             #[allow(clippy::missing_panics_doc)]
-            std::str::from_utf8(&self.0[0..2]).expect("Non-UTF-8 bytes in LanguageTag")
+            core::str::from_utf8(&self.0[0..2]).expect("Non-UTF-8 bytes in LanguageTag")
         }
         #[cfg(feature = "bcp_47")]
         {
@@ -68,7 +68,7 @@ impl LanguageTag {
             let slice = &self.0[2..4];
             #[allow(clippy::missing_panics_doc)]
             (*slice != [0, 0])
-                .then(|| std::str::from_utf8(slice).expect("Non-UTF-8 bytes in LanguageTag"))
+                .then(|| core::str::from_utf8(slice).expect("Non-UTF-8 bytes in LanguageTag"))
         }
         #[cfg(feature = "bcp_47")]
         {
@@ -125,8 +125,8 @@ impl From<unic_locale::Locale> for LanguageTag {
     }
 }
 
-impl std::fmt::Display for LanguageTag {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         #[cfg(not(feature = "bcp_47"))]
         if let Some(region) = self.region() {
             write!(f, "{}-{}", self.primary_language(), region)
@@ -140,7 +140,7 @@ impl std::fmt::Display for LanguageTag {
     }
 }
 
-impl std::str::FromStr for LanguageTag {
+impl core::str::FromStr for LanguageTag {
     type Err = UnknownValueError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
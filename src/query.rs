@@ -0,0 +1,253 @@
+#[cfg(feature = "ec4rs_glob")]
+#[cfg(test)]
+mod tests;
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::glob::Pattern;
+use crate::version::SpecVersion;
+use crate::{ConfigStack, Encoding, Error, ErrorPolicy, Properties, PropertiesSource};
+use crate::{SearchOptions, SymlinkPolicy};
+
+/// Which fallback values a [`Query`] adds to the properties it resolves.
+///
+/// See [`Properties::use_fallbacks`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Fallbacks {
+    /// Add no fallback values.
+    #[default]
+    None,
+    /// Add the fallback values of the current version of EditorConfig,
+    /// as [`Properties::use_fallbacks`] does.
+    Current,
+    /// Add the fallback values of versions before 0.9.0,
+    /// as [`Properties::use_fallbacks_legacy`] does.
+    Legacy,
+}
+
+impl Fallbacks {
+    /// Returns the fallback values of `version` of EditorConfig.
+    ///
+    /// See [`Properties::use_fallbacks_for`].
+    #[must_use]
+    pub fn for_version(version: SpecVersion) -> Self {
+        if version.has_legacy_fallbacks() {
+            Fallbacks::Legacy
+        } else {
+            Fallbacks::Current
+        }
+    }
+}
+
+/// A builder for resolving the [`Properties`] of a file,
+/// as a configurable alternative to [`properties_of`][crate::properties_of].
+///
+/// A query searches for EditorConfig files according to its [`SearchOptions`],
+/// using `P` to match section headers.
+/// The properties from those files are combined with any sources added to the query
+/// in a [`ConfigStack`], so that e.g. overrides take precedence over EditorConfig files.
+/// Fallback values are added last.
+///
+/// ```no_run
+/// # #[cfg(feature = "ec4rs_glob")] {
+/// use ec4rs::property::IndentSize;
+/// use ec4rs::{ErrorPolicy, Fallbacks, Query};
+/// let props = Query::<ec4rs::glob::Glob>::new()
+///     .with_error_policy(ErrorPolicy::Strict)
+///     .stop_at_vcs_root()
+///     .with_fallbacks(Fallbacks::Current)
+///     .with_overrides([("max_line_length", "100")])
+///     .properties_of("/home/user/repo/src/main.rs")
+///     .unwrap();
+/// let indent_size = props.get::<IndentSize>().unwrap_or_default();
+/// # }
+/// ```
+pub struct Query<'a, P: Pattern> {
+    options: SearchOptions,
+    fallbacks: Fallbacks,
+    stack: ConfigStack<'a>,
+    pattern: core::marker::PhantomData<fn() -> P>,
+}
+
+impl<P: Pattern> Default for Query<'_, P> {
+    fn default() -> Self {
+        Query {
+            options: SearchOptions::default(),
+            fallbacks: Fallbacks::default(),
+            stack: ConfigStack::default(),
+            pattern: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, P: Pattern + 'a> Query<'a, P> {
+    /// Constructs a query that behaves as [`properties_of`][crate::properties_of] does.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `self` set to search for EditorConfig files according to `options`,
+    /// replacing any search options that were set before.
+    #[must_use]
+    pub fn with_search_options(mut self, options: SearchOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns `self` set to look for EditorConfig files named `names`.
+    ///
+    /// A single absolute path is used as the only EditorConfig file.
+    /// See [`SearchOptions::with_config_names`].
+    #[must_use]
+    pub fn with_config_names(
+        mut self,
+        names: impl IntoIterator<Item = impl Into<PathBuf>>,
+    ) -> Self {
+        self.options = self.options.with_config_names(names);
+        self
+    }
+
+    /// Returns `self` set to handle EditorConfig files that cannot be opened
+    /// according to `policy`.
+    ///
+    /// See [`SearchOptions::with_error_policy`].
+    #[must_use]
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.options = self.options.with_error_policy(policy);
+        self
+    }
+
+    /// Returns `self` set to stop searching after the directory `dir`.
+    ///
+    /// See [`SearchOptions::stop_at`].
+    #[must_use]
+    pub fn stop_at(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.options = self.options.stop_at(dir);
+        self
+    }
+
+    /// Returns `self` set to stop searching after the root of a repository.
+    ///
+    /// See [`SearchOptions::stop_at_vcs_root`].
+    #[must_use]
+    pub fn stop_at_vcs_root(mut self) -> Self {
+        self.options = self.options.stop_at_vcs_root();
+        self
+    }
+
+    /// Returns `self` set to stop searching after a directory containing `marker`.
+    ///
+    /// See [`SearchOptions::stop_at_marker`].
+    #[must_use]
+    pub fn stop_at_marker(mut self, marker: impl Into<OsString>) -> Self {
+        self.options = self.options.stop_at_marker(marker);
+        self
+    }
+
+    /// Returns `self` set to stop searching after the user's home directory.
+    ///
+    /// See [`SearchOptions::stop_at_home`].
+    #[must_use]
+    pub fn stop_at_home(mut self) -> Self {
+        self.options = self.options.stop_at_home();
+        self
+    }
+
+    /// Returns `self` set to treat symbolic links according to `policy`.
+    ///
+    /// See [`SearchOptions::with_symlink_policy`].
+    #[must_use]
+    pub fn with_symlink_policy(mut self, policy: SymlinkPolicy) -> Self {
+        self.options = self.options.with_symlink_policy(policy);
+        self
+    }
+
+    /// Returns `self` set to decode EditorConfig files that are not in UTF-16 as `encoding`.
+    ///
+    /// See [`SearchOptions::with_encoding`].
+    #[must_use]
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.options = self.options.with_encoding(encoding);
+        self
+    }
+
+    /// Returns `self` set to parse EditorConfig files as `version` of EditorConfig would.
+    ///
+    /// This does not change which fallback values are added;
+    /// use [`Fallbacks::for_version`] for that.
+    /// See [`SearchOptions::with_version`].
+    #[must_use]
+    pub fn with_version(mut self, version: SpecVersion) -> Self {
+        self.options = self.options.with_version(version);
+        self
+    }
+
+    /// Returns `self` set to add the fallback values `fallbacks`.
+    #[must_use]
+    pub fn with_fallbacks(mut self, fallbacks: Fallbacks) -> Self {
+        self.fallbacks = fallbacks;
+        self
+    }
+
+    /// Returns `self` with `source` added to the defaults,
+    /// which EditorConfig files take precedence over.
+    ///
+    /// See [`ConfigStack::with_defaults`].
+    #[must_use]
+    pub fn with_defaults(mut self, source: impl PropertiesSource + 'a) -> Self {
+        self.stack = self.stack.with_defaults(source);
+        self
+    }
+
+    /// Returns `self` with the global EditorConfig file at `path`,
+    /// which EditorConfig files in the project take precedence over.
+    ///
+    /// See [`ConfigStack::with_global_file`].
+    #[must_use]
+    pub fn with_global_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.stack = self.stack.with_global_file::<P>(path);
+        self
+    }
+
+    /// Returns `self` with `source` added to the overrides,
+    /// which take precedence over EditorConfig files.
+    ///
+    /// See [`ConfigStack::with_overrides`].
+    #[must_use]
+    pub fn with_overrides(mut self, source: impl PropertiesSource + 'a) -> Self {
+        self.stack = self.stack.with_overrides(source);
+        self
+    }
+
+    /// Retrieves the [`Properties`] for a file at `target_path`.
+    ///
+    /// `target_path` should ideally be an absolute path.
+    /// If it is not, it is made absolute using [`std::path::absolute`].
+    pub fn properties_of(self, target_path: impl AsRef<Path>) -> Result<Properties, Error> {
+        let target_path = target_path.as_ref();
+        let mut retval = Properties::new();
+        self.stack
+            .with_project_search::<P>(self.options)
+            .apply_to(&mut retval, target_path)?;
+        match self.fallbacks {
+            Fallbacks::None => (),
+            Fallbacks::Current => retval.use_fallbacks(),
+            Fallbacks::Legacy => retval.use_fallbacks_legacy(),
+        }
+        Ok(retval)
+    }
+
+    /// Retrieves the properties for a file at `target_path` as a settings type `T`,
+    /// such as a struct of the properties a program uses.
+    ///
+    /// See [`Query::properties_of`].
+    pub fn settings_of<T: From<Properties>>(
+        self,
+        target_path: impl AsRef<Path>,
+    ) -> Result<T, Error> {
+        self.properties_of(target_path).map(T::from)
+    }
+}
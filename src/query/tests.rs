@@ -0,0 +1,81 @@
+use crate::glob::Glob;
+use crate::property::{IndentSize, IndentStyle};
+use crate::tests::TempTree;
+use crate::version::SpecVersion;
+use crate::{Fallbacks, Properties, Query};
+
+/// Creates a directory with EditorConfig files for the test `name`.
+fn dir(name: &str) -> TempTree {
+    let dir = TempTree::new(&format!("query-{name}"));
+    let text = "root = true\n[*.rs]\nindent_style = tab\ncharset = utf-8 # comment\n";
+    dir.write(".editorconfig", text);
+    dir.write(".editorconfig.local", "[*.rs]\nindent_style = space\n");
+    dir
+}
+
+/// Returns a query that stops searching at `dir`.
+fn query(dir: &TempTree) -> Query<'static, Glob> {
+    Query::new().stop_at(&dir.0)
+}
+
+fn get(props: &Properties, key: &str) -> Option<String> {
+    props.get_raw_for_key(key).map(|v| v.as_str().to_owned())
+}
+
+#[test]
+fn layers_and_fallbacks() {
+    let dir = dir("layers");
+    let target = dir.0.join("a.rs");
+    let props = query(&dir).properties_of(&target).unwrap();
+    assert_eq!(props.get::<IndentStyle>().unwrap(), IndentStyle::Tabs);
+    assert!(props.get::<IndentSize>().is_err());
+    let props = query(&dir)
+        .with_fallbacks(Fallbacks::Current)
+        .with_defaults([("indent_style", "space"), ("tab_width", "8")])
+        .with_overrides([("charset", "latin1")])
+        .properties_of(&target)
+        .unwrap();
+    assert_eq!(props.get::<IndentSize>().unwrap(), IndentSize::Value(8));
+    assert_eq!(get(&props, "charset").as_deref(), Some("latin1"));
+    let props = query(&dir)
+        .with_config_names([".editorconfig", ".editorconfig.local"])
+        .properties_of(&target)
+        .unwrap();
+    assert_eq!(props.get::<IndentStyle>().unwrap(), IndentStyle::Spaces);
+}
+
+#[test]
+fn version() {
+    let dir = dir("version");
+    let target = dir.0.join("a.rs");
+    let old = SpecVersion::new(0, 8, 0);
+    let props = query(&dir)
+        .with_version(old)
+        .with_fallbacks(Fallbacks::for_version(old))
+        .properties_of(&target)
+        .unwrap();
+    assert_eq!(get(&props, "charset").as_deref(), Some("utf-8"));
+    assert!(props.get::<IndentSize>().is_err());
+    let props = query(&dir).properties_of(&target).unwrap();
+    assert_eq!(get(&props, "charset").as_deref(), Some("utf-8 # comment"));
+}
+
+#[test]
+fn settings() {
+    struct Settings {
+        indent_style: IndentStyle,
+    }
+    impl From<Properties> for Settings {
+        fn from(props: Properties) -> Self {
+            Settings {
+                indent_style: props.get().unwrap_or_default(),
+            }
+        }
+    }
+    let dir = dir("settings");
+    let settings: Settings = query(&dir)
+        .with_config_names([dir.0.join(".editorconfig.local")])
+        .settings_of(dir.0.join("a.rs"))
+        .unwrap();
+    assert_eq!(settings.indent_style, IndentStyle::Spaces);
+}
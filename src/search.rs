@@ -0,0 +1,306 @@
+#[cfg(feature = "ec4rs_glob")]
+#[cfg(test)]
+mod tests;
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::limits::Limits;
+use crate::version::SpecVersion;
+use crate::Encoding;
+
+/// How symbolic links in the path of the target file are treated
+/// when searching for EditorConfig files and matching sections.
+///
+/// Resolving symbolic links requires the target file or some of its ancestors to exist.
+/// The part of the path that doesn't exist is joined onto the real path of the rest.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum SymlinkPolicy {
+    /// Use the absolute path of the target file, without resolving symbolic links.
+    ///
+    /// A file reached through a symbolic link to a directory
+    /// uses the EditorConfig files around the link.
+    #[default]
+    Lexical,
+    /// Use the canonical path of the target file, with every symbolic link resolved.
+    Canonical,
+    /// Use both the absolute and canonical paths of the target file.
+    ///
+    /// The EditorConfig files found from the canonical path take precedence
+    /// over those found only from the absolute path.
+    Both,
+}
+
+/// How errors while opening EditorConfig files are handled when searching for them.
+///
+/// Files that don't exist are always skipped.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum ErrorPolicy {
+    /// Skip files that cannot be opened or whose preamble cannot be read,
+    /// unless a [`Limits`] was exceeded.
+    ///
+    /// This matches the behavior of other EditorConfig cores,
+    /// but means that an unreadable file with `root = true`
+    /// lets files in its ancestor directories apply.
+    #[default]
+    Lenient,
+    /// Return an error, with the path of the file, for any file that exists
+    /// but cannot be opened or whose preamble cannot be read,
+    /// e.g. due to its permissions.
+    Strict,
+}
+
+/// Options for how [`ConfigFiles`][crate::ConfigFiles] searches for EditorConfig files.
+///
+/// By default, every ancestor directory of the target path is searched
+/// until an EditorConfig file with `root = true` is found.
+/// The search can also be bounded by a given directory, the root of a repository,
+/// or the user's home directory.
+/// The search stops after the bounding directory has been searched,
+/// so its own EditorConfig file is still used.
+///
+/// Directories are compared as absolute paths, without resolving symbolic links.
+///
+/// ```no_run
+/// # #[cfg(feature = "ec4rs_glob")] {
+/// use ec4rs::{ConfigFiles, SearchOptions};
+/// let options = SearchOptions::new().stop_at_vcs_root().stop_at_home();
+/// let files = ConfigFiles::<ec4rs::glob::Glob>::open_with_options(
+///     "/home/user/repo/src/main.rs",
+///     Option::<&str>::None,
+///     &options,
+/// );
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SearchOptions {
+    config_names: Vec<PathBuf>,
+    limits: Limits,
+    encoding: Encoding,
+    stop_dirs: Vec<PathBuf>,
+    vcs_markers: Vec<OsString>,
+    stop_at_home: bool,
+    ignore_root: bool,
+    symlink_policy: SymlinkPolicy,
+    error_policy: ErrorPolicy,
+    version: SpecVersion,
+}
+
+impl SearchOptions {
+    /// Constructs the default options, which search every ancestor directory.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `self` set to look for EditorConfig files named `names` in each directory,
+    /// instead of just `.editorconfig`.
+    ///
+    /// Files in the same directory apply in the order of their names,
+    /// so that later names take precedence, e.g. `[".editorconfig", ".editorconfig.local"]`.
+    /// Files in a directory all take precedence over files in its ancestors.
+    /// If any of the files in a directory has `root = true`,
+    /// the search stops after that directory, but every file in it still applies.
+    ///
+    /// If `names` is empty, `.editorconfig` is used.
+    #[must_use]
+    pub fn with_config_names(
+        mut self,
+        names: impl IntoIterator<Item = impl Into<PathBuf>>,
+    ) -> Self {
+        self.config_names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns `self` set to stop reading once any of `limits` is exceeded.
+    ///
+    /// See [`ConfigFiles::open_with_limits`][crate::ConfigFiles::open_with_limits].
+    #[must_use]
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Returns `self` set to decode files that are not in UTF-16 as `encoding`.
+    ///
+    /// This only applies to [`ConfigFiles`][crate::ConfigFiles] that read files
+    /// with a [`DecodingReader`][crate::DecodingReader], which [`Query`][crate::Query] does.
+    /// See [`ConfigFile::open_with_encoding`][crate::ConfigFile::open_with_encoding].
+    #[must_use]
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Returns `self` set to stop searching after the directory `dir`.
+    ///
+    /// This may be called more than once to stop at whichever directory is found first.
+    /// A relative `dir` is made absolute when searching.
+    #[must_use]
+    pub fn stop_at(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.stop_dirs.push(dir.into());
+        self
+    }
+
+    /// Returns `self` set to stop searching after a directory containing a `.git` or `.hg` entry.
+    ///
+    /// See [`SearchOptions::stop_at_marker`] for other version control systems.
+    #[must_use]
+    pub fn stop_at_vcs_root(self) -> Self {
+        self.stop_at_marker(".git").stop_at_marker(".hg")
+    }
+
+    /// Returns `self` set to stop searching after a directory containing an entry named `marker`,
+    /// such as `.svn`.
+    #[must_use]
+    pub fn stop_at_marker(mut self, marker: impl Into<OsString>) -> Self {
+        self.vcs_markers.push(marker.into());
+        self
+    }
+
+    /// Returns `self` set to stop searching after the user's home directory.
+    ///
+    /// The home directory is taken from the `HOME` environment variable,
+    /// or `USERPROFILE` on Windows, when searching.
+    /// If it isn't set to an absolute path, this has no effect.
+    #[must_use]
+    pub fn stop_at_home(mut self) -> Self {
+        self.stop_at_home = true;
+        self
+    }
+
+    /// Returns `self` set to keep searching past files with `root = true` if `ignore` is `true`.
+    ///
+    /// This is meant for auditing which EditorConfig files exist above a file.
+    /// The search is still bounded by any other options.
+    #[must_use]
+    pub fn with_ignore_root(mut self, ignore: bool) -> Self {
+        self.ignore_root = ignore;
+        self
+    }
+
+    /// Returns `self` set to treat symbolic links according to `policy`.
+    ///
+    /// The policy applies both to searching for EditorConfig files
+    /// and to the path that sections are matched against.
+    /// Directories to stop at are also resolved when searching from the canonical path.
+    #[must_use]
+    pub fn with_symlink_policy(mut self, policy: SymlinkPolicy) -> Self {
+        self.symlink_policy = policy;
+        self
+    }
+
+    /// Returns `self` set to handle files that cannot be opened according to `policy`.
+    #[must_use]
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    /// Returns `self` set to parse the files that are found as `version` of EditorConfig would.
+    ///
+    /// See [`ConfigFiles::with_version`][crate::ConfigFiles::with_version].
+    #[must_use]
+    pub fn with_version(mut self, version: SpecVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Returns the names of EditorConfig files, in the order that they apply in.
+    pub fn config_names(&self) -> impl Iterator<Item = &Path> {
+        let default = self
+            .config_names
+            .is_empty()
+            .then(|| Path::new(".editorconfig"));
+        self.config_names
+            .iter()
+            .map(PathBuf::as_path)
+            .chain(default)
+    }
+
+    /// Returns the limits to enforce while searching.
+    #[must_use]
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Returns the encoding of files that are not in UTF-16.
+    #[must_use]
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Returns `true` if files with `root = true` do not stop the search.
+    #[must_use]
+    pub fn ignores_root(&self) -> bool {
+        self.ignore_root
+    }
+
+    /// Returns how symbolic links are treated.
+    #[must_use]
+    pub fn symlink_policy(&self) -> SymlinkPolicy {
+        self.symlink_policy
+    }
+
+    /// Returns how files that cannot be opened are handled.
+    #[must_use]
+    pub fn error_policy(&self) -> ErrorPolicy {
+        self.error_policy
+    }
+
+    /// Returns the version of EditorConfig that files are parsed as.
+    #[must_use]
+    pub fn version(&self) -> SpecVersion {
+        self.version
+    }
+
+    /// Returns the absolute paths of the directories to stop at, including the home directory.
+    ///
+    /// If `canonical` is `true`, symbolic links in the paths are resolved.
+    pub(crate) fn stop_dirs(&self, canonical: bool) -> std::io::Result<Vec<PathBuf>> {
+        let mut dirs = self
+            .stop_dirs
+            .iter()
+            .map(std::path::absolute)
+            .collect::<Result<Vec<_>, _>>()?;
+        if self.stop_at_home {
+            dirs.extend(home_dir());
+        }
+        if canonical {
+            for dir in &mut dirs {
+                *dir = resolve(dir);
+            }
+        }
+        Ok(dirs)
+    }
+
+    /// Returns `true` if the search should stop after `dir`.
+    pub(crate) fn is_boundary(&self, dir: &Path, stop_dirs: &[PathBuf]) -> bool {
+        stop_dirs.iter().any(|stop| stop == dir)
+            || self
+                .vcs_markers
+                .iter()
+                .any(|marker| dir.join(marker).exists())
+    }
+}
+
+/// Resolves the symbolic links in the absolute path `path`, as far as it exists.
+pub(crate) fn resolve(path: &Path) -> PathBuf {
+    for ancestor in path.ancestors() {
+        if let Ok(real) = std::fs::canonicalize(ancestor) {
+            return match path.strip_prefix(ancestor) {
+                Ok(rest) if !rest.as_os_str().is_empty() => real.join(rest),
+                _ => real,
+            };
+        }
+    }
+    path.to_owned()
+}
+
+fn home_dir() -> Option<PathBuf> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    let home = PathBuf::from(std::env::var_os(var)?);
+    home.is_absolute().then_some(home)
+}
@@ -0,0 +1,240 @@
+use std::fs;
+use std::path::Path;
+
+use crate::glob::Glob;
+use crate::tests::TempTree;
+use crate::{ConfigFiles, SearchOptions};
+
+/// Creates a directory tree of EditorConfig files for the test `name`.
+///
+/// ```text
+/// top/.editorconfig
+/// top/repo/.git/
+/// top/repo/.editorconfig
+/// top/repo/sub/.editorconfig (root = true)
+/// ```
+fn tree(name: &str) -> TempTree {
+    let tree = TempTree::new(name);
+    fs::create_dir_all(tree.0.join("repo/.git")).unwrap();
+    tree.write(".editorconfig", "[*]\n");
+    tree.write("repo/.editorconfig", "[*]\n");
+    tree.write("repo/sub/.editorconfig", "root = true\n[*]\n");
+    tree
+}
+
+/// Returns the directories of the EditorConfig files found for `target`, innermost first.
+/// Directories are relative to the top of the tree and `/`-separated.
+fn search(tree: &TempTree, target: &str, options: &SearchOptions) -> Vec<String> {
+    let files = ConfigFiles::<Glob>::open_with_options(tree.0.join(target), None::<&Path>, options)
+        .unwrap();
+    let mut dirs: Vec<_> = files
+        .iter()
+        .map(|file| {
+            let dir = file.path.parent().unwrap().strip_prefix(&tree.0).unwrap();
+            let names: Vec<_> = dir.iter().map(|name| name.to_str().unwrap()).collect();
+            names.join("/")
+        })
+        .collect();
+    dirs.reverse();
+    dirs
+}
+
+#[test]
+fn boundaries() {
+    let tree = tree("boundaries");
+    // Stop at the top of the tree so that nothing outside of it is found.
+    let top = || SearchOptions::new().stop_at(&tree.0);
+    let repo = tree.0.join("repo");
+    assert_eq!(search(&tree, "repo/a.rs", &top()), ["repo", ""]);
+    assert_eq!(
+        search(&tree, "repo/a.rs", &top().stop_at_vcs_root()),
+        ["repo"]
+    );
+    assert_eq!(search(&tree, "repo/a.rs", &top().stop_at(repo)), ["repo"]);
+    assert_eq!(
+        search(&tree, "repo/a.rs", &top().stop_at_marker(".svn")),
+        ["repo", ""]
+    );
+    assert_eq!(search(&tree, "a.rs", &top().stop_at_vcs_root()), [""]);
+    assert_eq!(search(&tree, "repo/sub/a.rs", &top()), ["repo/sub"]);
+}
+
+#[test]
+fn ignore_root() {
+    let tree = tree("ignore-root");
+    let options = SearchOptions::new().stop_at(&tree.0).with_ignore_root(true);
+    let expected = ["repo/sub", "repo", ""];
+    assert_eq!(search(&tree, "repo/sub/a.rs", &options), expected);
+    let options = options.stop_at_vcs_root();
+    assert_eq!(
+        search(&tree, "repo/sub/a.rs", &options),
+        ["repo/sub", "repo"]
+    );
+}
+
+#[test]
+fn config_names() {
+    use crate::PropertiesSource;
+    let tree = tree("config-names");
+    tree.write(
+        "repo/.editorconfig.local",
+        "root = true\n[*]\nindent_size = 8\n",
+    );
+    tree.write("repo/.editorconfig", "[*]\nindent_size = 2\n");
+    let options = SearchOptions::new()
+        .stop_at(&tree.0)
+        .with_config_names([".editorconfig", ".editorconfig.local"]);
+    let target = tree.0.join("repo/a.rs");
+    let files = ConfigFiles::<Glob>::open_with_options(&target, None::<&Path>, &options).unwrap();
+    let names: Vec<_> = files
+        .iter()
+        .map(|file| file.path.strip_prefix(&tree.0).unwrap().to_owned())
+        .collect();
+    let expected =
+        [".editorconfig", ".editorconfig.local"].map(|name| Path::new("repo").join(name));
+    assert_eq!(names, expected);
+    let mut props = crate::Properties::new();
+    files.apply_to(&mut props, &target).unwrap();
+    assert_eq!(props.get_raw_for_key("indent_size").unwrap().as_str(), "8");
+    // Both files in a directory apply even if the one with `root = true` comes first.
+    let options = options.with_config_names([".editorconfig.local", ".editorconfig"]);
+    let mut props = crate::Properties::new();
+    ConfigFiles::<Glob>::open_with_options(&target, None::<&Path>, &options)
+        .unwrap()
+        .apply_to(&mut props, &target)
+        .unwrap();
+    assert_eq!(props.get_raw_for_key("indent_size").unwrap().as_str(), "2");
+    let options = options.with_config_names([".editorconfig", "/.editorconfig"]);
+    assert!(ConfigFiles::<Glob>::open_with_options(&target, None::<&Path>, &options).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks() {
+    use crate::{PropertiesSource, SymlinkPolicy};
+    let tree = tree("symlinks");
+    fs::create_dir_all(tree.0.join("repo/dir")).unwrap();
+    fs::create_dir_all(tree.0.join("a")).unwrap();
+    std::os::unix::fs::symlink(tree.0.join("repo/dir"), tree.0.join("a/link")).unwrap();
+    let top = "[repo/dir/*.rs]\nend_of_line = lf\n[a/link/*.rs]\nend_of_line = crlf\n";
+    tree.write(".editorconfig", top);
+    tree.write(
+        "a/.editorconfig",
+        "[*]\nindent_size = 1\nindent_style = tab\n",
+    );
+    tree.write("repo/.editorconfig", "[*]\nindent_size = 2\n");
+    let target = tree.0.join("a/link/b.rs");
+    let get = |policy, key| {
+        let options = SearchOptions::new()
+            .stop_at(&tree.0)
+            .with_symlink_policy(policy);
+        let mut props = crate::Properties::new();
+        ConfigFiles::<Glob>::open_with_options(&target, None::<&Path>, &options)
+            .unwrap()
+            .apply_to(&mut props, &target)
+            .unwrap();
+        props
+            .get_raw_for_key(key)
+            .map(|value| value.as_str().to_owned())
+    };
+    let search = |policy| {
+        let options = SearchOptions::new()
+            .stop_at(&tree.0)
+            .with_symlink_policy(policy);
+        search(&tree, "a/link/b.rs", &options)
+    };
+    assert_eq!(search(SymlinkPolicy::Lexical), ["a", ""]);
+    assert_eq!(get(SymlinkPolicy::Lexical, "end_of_line").unwrap(), "crlf");
+    assert_eq!(get(SymlinkPolicy::Lexical, "indent_size").unwrap(), "1");
+    assert_eq!(search(SymlinkPolicy::Canonical), ["repo", ""]);
+    assert_eq!(get(SymlinkPolicy::Canonical, "end_of_line").unwrap(), "lf");
+    assert_eq!(get(SymlinkPolicy::Canonical, "indent_style"), None);
+    assert_eq!(search(SymlinkPolicy::Both), ["repo", "", "a"]);
+    assert_eq!(get(SymlinkPolicy::Both, "end_of_line").unwrap(), "lf");
+    assert_eq!(get(SymlinkPolicy::Both, "indent_size").unwrap(), "2");
+    assert_eq!(get(SymlinkPolicy::Both, "indent_style").unwrap(), "tab");
+}
+
+#[test]
+fn error_policy() {
+    use crate::{Error, ErrorPolicy, ParseError};
+    let tree = tree("error-policy");
+    // Invalid UTF-8 in the preamble and a directory where a file is expected.
+    tree.write("repo/.editorconfig", b"root = \xff\n");
+    fs::create_dir(tree.0.join("repo/sub/.ec")).unwrap();
+    let options = SearchOptions::new().stop_at(&tree.0);
+    assert_eq!(search(&tree, "repo/a.rs", &options), [""]);
+    let open = |target: &str, options: &SearchOptions| {
+        ConfigFiles::<Glob>::open_with_options(tree.0.join(target), None::<&Path>, options)
+            .err()
+            .unwrap()
+    };
+    let strict = options.with_error_policy(ErrorPolicy::Strict);
+    let Error::Parse(ParseError::Io(_), Some(source)) = open("repo/a.rs", &strict) else {
+        panic!("expected an IO error with a source");
+    };
+    assert_eq!(source.get().0, tree.0.join("repo/.editorconfig"));
+    assert_eq!(source.line(), Some(1));
+    let strict = strict.with_config_names([".ec"]);
+    let Error::Parse(ParseError::Io(_), Some(source)) = open("repo/sub/a.rs", &strict) else {
+        panic!("expected an IO error with a source");
+    };
+    assert_eq!(source.get().0, tree.0.join("repo/sub/.ec"));
+    assert_eq!(source.line(), Some(1));
+    // Missing files are still skipped.
+    assert_eq!(search(&tree, "a.rs", &strict), Vec::<String>::new());
+    // An absolute config name is reported with its path regardless of the policy.
+    let missing = tree.0.join("missing");
+    let error = ConfigFiles::<Glob>::open(tree.0.join("a.rs"), Some(&missing)).err();
+    let Some(Error::Parse(ParseError::Io(_), Some(source))) = error else {
+        panic!("expected an IO error with a source");
+    };
+    assert_eq!(source.get().0, missing);
+    assert_eq!(source.line(), None);
+    assert_eq!(source.to_string(), missing.to_string_lossy());
+}
+
+#[test]
+fn limit_source() {
+    use crate::limits::{LimitExceeded, Limits};
+    use crate::{Error, ParseError};
+    let tree = tree("limit-source");
+    tree.write("repo/.editorconfig", "root = true\nlong_key = 1\n");
+    let options = SearchOptions::new().with_limits(Limits::default().with_max_line_len(11));
+    let error =
+        ConfigFiles::<Glob>::open_with_options(tree.0.join("repo/a.rs"), None::<&Path>, &options)
+            .err();
+    let Some(Error::Parse(ParseError::LimitExceeded(LimitExceeded::LineLength(11)), Some(source))) =
+        error
+    else {
+        panic!("expected a line length error with a source");
+    };
+    assert_eq!(source.get().0, tree.0.join("repo/.editorconfig"));
+    assert_eq!(source.line(), Some(2));
+}
+
+#[test]
+fn encoding() {
+    use crate::{ConfigFile, DecodingReader, Encoding, PropertiesSource};
+    type Decoded = DecodingReader<std::io::BufReader<fs::File>>;
+    let tree = TempTree::new("encoding");
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in "root = true\n[*]\nfoo = bär\n".encode_utf16() {
+        bytes.extend(unit.to_le_bytes());
+    }
+    tree.write(".editorconfig", bytes);
+    let path = tree.0.join(".editorconfig");
+    // Files are read as UTF-8 unless they are decoded.
+    assert!(ConfigFile::<Glob>::open(&path).is_err());
+    let file = ConfigFile::<Glob, Decoded>::open_with_encoding(&path, Encoding::Utf8).unwrap();
+    assert!(file.reader.preamble.is_root());
+    let files = ConfigFiles::<Glob, Decoded>::open_with_options(
+        tree.0.join("a.rs"),
+        None::<&Path>,
+        &SearchOptions::new(),
+    )
+    .unwrap();
+    let mut props = crate::Properties::new();
+    files.apply_to(&mut props, tree.0.join("a.rs")).unwrap();
+    assert_eq!(props.get_raw_for_key("foo").unwrap().as_str(), "bär");
+}
@@ -1,8 +1,10 @@
 use crate::glob::Pattern;
 use crate::properties::PropertiesSink;
 use crate::string::{ParseError, ToSharedString};
+use crate::version::SpecVersion;
 use crate::Properties;
 
+#[cfg(not(feature = "no_std"))]
 use std::path::Path;
 
 // Glob internals aren't stable enough to safely implement PartialEq here.
@@ -12,6 +14,8 @@ use std::path::Path;
 pub struct Section<P: Pattern> {
     pattern: Result<P, ParseError<P::Error>>,
     props: crate::Properties,
+    /// The version the pattern was parsed with.
+    version: SpecVersion,
 }
 
 impl<P: Pattern> Section<P> {
@@ -22,12 +26,17 @@ impl<P: Pattern> Section<P> {
     /// either [`or_err`][Self::or_err] or [`pattern`][Self::pattern].
     #[must_use]
     pub fn new(pattern: &str) -> Self {
+        Self::new_with_version(pattern, SpecVersion::CURRENT)
+    }
+    /// As [`Section::new`], but parses the pattern using [`Pattern::parse_with_version`].
+    pub(crate) fn new_with_version(pattern: &str, version: SpecVersion) -> Self {
         Section {
-            pattern: P::parse(pattern).map_err(|error| ParseError {
+            pattern: P::parse_with_version(pattern, version).map_err(|error| ParseError {
                 error,
                 string: pattern.into(),
             }),
             props: crate::Properties::new(),
+            version,
         }
     }
     /// Returns `Ok(self)` if there was no pattern parse error,
@@ -40,6 +49,7 @@ impl<P: Pattern> Section<P> {
         }
     }
     /// Returns true if and only if this section applies to a file at the specified path.
+    #[cfg(not(feature = "no_std"))]
     pub fn applies_to(&self, path: impl AsRef<Path>) -> bool {
         // MSRV of 1.56 prevents use of is_ok_and from 1.70.
         match self.pattern.as_ref() {
@@ -47,10 +57,24 @@ impl<P: Pattern> Section<P> {
             _ => false,
         }
     }
+    /// Returns true if and only if this section applies to a file
+    /// at the specified `/`-separated path.
+    ///
+    /// See [`Pattern::matches_str`].
+    pub fn applies_to_str(&self, path: &str) -> bool {
+        match self.pattern.as_ref() {
+            Ok(p) => p.matches_str(path),
+            _ => false,
+        }
+    }
     /// Returns a reference to either the pattern or the error.
     pub fn pattern(&self) -> &Result<P, ParseError<P::Error>> {
         &self.pattern
     }
+    /// Returns the version of EditorConfig that the pattern was parsed for.
+    pub fn version(&self) -> SpecVersion {
+        self.version
+    }
     /// Returns a shared reference to the internal [`Properties`] map.
     pub fn props(&self) -> &Properties {
         &self.props
@@ -70,6 +94,51 @@ impl<P: Pattern> Section<P> {
     }
 }
 
+/// Serializes the section's pattern, the [`SpecVersion`] it was parsed for, and its properties.
+///
+/// If the pattern failed to parse, its text is serialized in its place.
+/// Deserializing the section then parses that text again with that version
+/// to recover the error.
+#[cfg(feature = "serde")]
+impl<P: Pattern + serde::Serialize> serde::Serialize for Section<P> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let pattern = self.pattern.as_ref().map_err(|e| e.string.as_str());
+        let mut state = serializer.serialize_struct("Section", 3)?;
+        state.serialize_field("pattern", &pattern)?;
+        state.serialize_field("version", &self.version)?;
+        state.serialize_field("props", &self.props)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, P: Pattern + serde::Deserialize<'de>> serde::Deserialize<'de> for Section<P> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Section")]
+        struct Repr<P> {
+            pattern: Result<P, crate::string::SharedString>,
+            version: SpecVersion,
+            props: Properties,
+        }
+        let Repr {
+            pattern,
+            version,
+            props,
+        } = Repr::deserialize(deserializer)?;
+        let pattern = pattern.or_else(|string| {
+            P::parse_with_version(string.as_str(), version)
+                .map_err(|error| ParseError { error, string })
+        });
+        Ok(Section {
+            pattern,
+            props,
+            version,
+        })
+    }
+}
+
 impl<P: Pattern> PropertiesSink for Section<P> {
     fn add_property(&mut self, key: impl ToSharedString, val: impl ToSharedString) {
         self.props.add_property(key, val)
@@ -82,6 +151,7 @@ impl<P: Pattern> PropertiesSink for Section<P> {
     }
 }
 
+#[cfg(not(feature = "no_std"))]
 impl<P: Pattern> crate::PropertiesSource for &Section<P> {
     /// Adds this section's properties to a [`Properties`].
     ///
@@ -0,0 +1,258 @@
+#[cfg(feature = "ec4rs_glob")]
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::file::Decoded;
+use crate::glob::Pattern;
+use crate::limits::Limits;
+use crate::string::{SharedString, ToSharedString};
+use crate::{ConfigFile, ConfigFiles, Encoding, Error, ParseError};
+use crate::{Properties, PropertiesSink, PropertiesSource, SearchOptions};
+
+/// A layer of a [`ConfigStack`], in order of increasing precedence.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[non_exhaustive]
+pub enum Layer {
+    /// Built-in defaults of the program.
+    Defaults,
+    /// The user's global configuration, e.g. `~/.config/editorconfig`.
+    Global,
+    /// The EditorConfig files of the project that the file is in.
+    Project,
+    /// Overrides from the command line or similar.
+    Overrides,
+}
+
+impl core::fmt::Display for Layer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Layer::Defaults => "defaults",
+            Layer::Global => "global",
+            Layer::Project => "project",
+            Layer::Overrides => "overrides",
+        })
+    }
+}
+
+/// A [`PropertiesSink`] that forwards properties to a closure.
+///
+/// This allows layers of a [`ConfigStack`] to apply to different kinds of sinks.
+struct LayerSink<'s> {
+    add: &'s mut dyn FnMut(SharedString, SharedString),
+}
+
+impl PropertiesSink for LayerSink<'_> {
+    fn add_property(&mut self, key: impl ToSharedString, val: impl ToSharedString) {
+        (self.add)(key.to_shared_string(), val.to_shared_string());
+    }
+}
+
+type LayerFn<'a> = Box<dyn FnOnce(&mut LayerSink<'_>, &Path) -> Result<(), Error> + 'a>;
+
+/// A stack of [`PropertiesSource`]s in [`Layer`]s,
+/// for combining EditorConfig files with configuration from elsewhere.
+///
+/// Layers apply in the order of [`Layer`], regardless of the order they were added in,
+/// so that properties from later layers take precedence.
+/// Sources in the same layer apply in the order they were added in.
+///
+/// ```
+/// # #[cfg(feature = "ec4rs_glob")] {
+/// use ec4rs::{ConfigStack, Layer, SearchOptions};
+/// let stack = ConfigStack::new()
+///     .with_overrides([("indent_size", "8")])
+///     .with_defaults([("indent_style", "space"), ("indent_size", "4")])
+///     .with_project_search::<ec4rs::glob::Glob>(SearchOptions::new());
+/// let props = stack.resolve("/nonexistent/file.rs").unwrap();
+/// assert_eq!(props.properties().get_raw_for_key("indent_size").unwrap().as_str(), "8");
+/// assert_eq!(props.layer_of("indent_size"), Some(Layer::Overrides));
+/// assert_eq!(props.layer_of("indent_style"), Some(Layer::Defaults));
+/// # }
+/// ```
+#[derive(Default)]
+pub struct ConfigStack<'a> {
+    layers: Vec<(Layer, LayerFn<'a>)>,
+}
+
+impl<'a> ConfigStack<'a> {
+    /// Constructs a new empty stack.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `self` with `source` added to `layer`.
+    #[must_use]
+    pub fn with_layer(mut self, layer: Layer, source: impl PropertiesSource + 'a) -> Self {
+        let apply: LayerFn<'a> = Box::new(move |sink, path| source.apply_to(sink, path));
+        self.layers.push((layer, apply));
+        self
+    }
+
+    /// Returns `self` with `source` added to [`Layer::Defaults`].
+    #[must_use]
+    pub fn with_defaults(self, source: impl PropertiesSource + 'a) -> Self {
+        self.with_layer(Layer::Defaults, source)
+    }
+
+    /// Returns `self` with `source` added to [`Layer::Global`].
+    #[must_use]
+    pub fn with_global(self, source: impl PropertiesSource + 'a) -> Self {
+        self.with_layer(Layer::Global, source)
+    }
+
+    /// Returns `self` with the EditorConfig file at `path` added to [`Layer::Global`].
+    ///
+    /// The file is opened when the stack is applied, and is skipped if it doesn't exist.
+    /// Sections are matched against the path of the target file,
+    /// relative to the directory containing `path` if it's in that directory.
+    #[must_use]
+    pub fn with_global_file<P: Pattern + 'a>(self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        self.with_layer(
+            Layer::Global,
+            GlobalFile::<P>(path, core::marker::PhantomData),
+        )
+    }
+
+    /// Returns `self` with `source` added to [`Layer::Project`].
+    #[must_use]
+    pub fn with_project(self, source: impl PropertiesSource + 'a) -> Self {
+        self.with_layer(Layer::Project, source)
+    }
+
+    /// Returns `self` with the EditorConfig files found for the target file
+    /// added to [`Layer::Project`].
+    ///
+    /// The files are searched for when the stack is applied,
+    /// using [`ConfigFiles::open_with_options`] with a [`DecodingReader`][crate::DecodingReader].
+    #[must_use]
+    pub fn with_project_search<P: Pattern + 'a>(self, options: SearchOptions) -> Self {
+        self.with_layer(
+            Layer::Project,
+            ProjectSearch::<P>(options, core::marker::PhantomData),
+        )
+    }
+
+    /// Returns `self` with `source` added to [`Layer::Overrides`].
+    #[must_use]
+    pub fn with_overrides(self, source: impl PropertiesSource + 'a) -> Self {
+        self.with_layer(Layer::Overrides, source)
+    }
+
+    /// Applies every layer for a file at `path`, recording which layer set each property.
+    pub fn resolve(self, path: impl AsRef<Path>) -> Result<LayeredProperties, Error> {
+        let mut retval = LayeredProperties::default();
+        self.apply_layers(path.as_ref(), |layer, key, value| {
+            retval.layers.insert(key.clone(), layer);
+            retval.props.insert_raw_for_key(key, value);
+        })?;
+        Ok(retval)
+    }
+
+    fn apply_layers(
+        mut self,
+        path: &Path,
+        mut add: impl FnMut(Layer, SharedString, SharedString),
+    ) -> Result<(), Error> {
+        self.layers.sort_by_key(|(layer, _)| *layer);
+        for (layer, apply) in self.layers {
+            let mut add = |key, value| add(layer, key, value);
+            apply(&mut LayerSink { add: &mut add }, path)?;
+        }
+        Ok(())
+    }
+}
+
+impl PropertiesSource for ConfigStack<'_> {
+    /// Applies every layer in order of precedence.
+    fn apply_to(
+        self,
+        props: &mut (impl PropertiesSink + ?Sized),
+        path: impl AsRef<Path>,
+    ) -> Result<(), Error> {
+        self.apply_layers(path.as_ref(), |_, key, value| {
+            props.add_property(key, value);
+        })
+    }
+}
+
+/// A global EditorConfig file that may not exist.
+struct GlobalFile<P>(PathBuf, core::marker::PhantomData<fn() -> P>);
+
+impl<P: Pattern> PropertiesSource for GlobalFile<P> {
+    fn apply_to(
+        self,
+        props: &mut (impl PropertiesSink + ?Sized),
+        path: impl AsRef<Path>,
+    ) -> Result<(), Error> {
+        match ConfigFile::<P, Decoded>::open_with_context(&self.0, Encoding::Utf8, Limits::NONE) {
+            Ok(mut file) => file.apply_to(props, path),
+            Err(Error::Parse(ParseError::Io(e), _)) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// A search for project EditorConfig files from the target file.
+struct ProjectSearch<P>(SearchOptions, core::marker::PhantomData<fn() -> P>);
+
+impl<P: Pattern> PropertiesSource for ProjectSearch<P> {
+    fn apply_to(
+        self,
+        props: &mut (impl PropertiesSink + ?Sized),
+        path: impl AsRef<Path>,
+    ) -> Result<(), Error> {
+        let path = path.as_ref();
+        ConfigFiles::<P, Decoded>::open_with_options(path, Option::<&Path>::None, &self.0)?
+            .apply_to(props, path)
+    }
+}
+
+/// [`Properties`] resolved by a [`ConfigStack`], with the layer that set each of them.
+#[derive(Clone, Default)]
+pub struct LayeredProperties {
+    props: Properties,
+    layers: BTreeMap<SharedString, Layer>,
+}
+
+impl LayeredProperties {
+    /// Returns the resolved properties.
+    #[must_use]
+    pub fn properties(&self) -> &Properties {
+        &self.props
+    }
+
+    /// Returns the resolved properties, discarding the layers.
+    #[must_use]
+    pub fn into_properties(self) -> Properties {
+        self.props
+    }
+
+    /// Returns the layer whose value for `key` took precedence,
+    /// or `None` if no layer set `key`.
+    #[must_use]
+    pub fn layer_of(&self, key: impl AsRef<str>) -> Option<Layer> {
+        self.layers.get(key.as_ref()).copied()
+    }
+
+    /// Returns an iterator over the key-value pairs, from oldest to newest,
+    /// with the layer that set each of them.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &SharedString, Layer)> {
+        // Every key in `props` is also in `layers`.
+        (self.props.iter()).map(|(key, value)| (key, value, self.layers[key]))
+    }
+}
+
+impl core::fmt::Debug for LayeredProperties {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(key, value, layer)| (key, (value, layer))))
+            .finish()
+    }
+}
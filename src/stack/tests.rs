@@ -0,0 +1,69 @@
+use crate::stack::{ConfigStack, Layer};
+use crate::tests::TempTree;
+use crate::{Properties, PropertiesSource};
+
+#[test]
+fn precedence() {
+    let mut section = crate::Section::<crate::glob::Glob>::new("*.rs");
+    section.insert("indent_size", "2");
+    section.insert("tab_width", "8");
+    let mut other = crate::Section::<crate::glob::Glob>::new("*.md");
+    other.insert("indent_size", "3");
+    let stack = || {
+        ConfigStack::new()
+            .with_overrides([("tab_width", "4")])
+            .with_project(&section)
+            .with_project(&other)
+            .with_defaults([("indent_size", "4"), ("indent_style", "space")])
+            .with_global([("indent_style", "tab")])
+            .with_defaults([("end_of_line", "lf")])
+    };
+    let props = stack().resolve("src/lib.rs").unwrap();
+    let expected = [
+        ("indent_size", "2", Layer::Project),
+        ("indent_style", "tab", Layer::Global),
+        ("end_of_line", "lf", Layer::Defaults),
+        ("tab_width", "4", Layer::Overrides),
+    ];
+    let pairs: Vec<_> = props
+        .iter()
+        .map(|(key, value, layer)| (key, value.as_str(), layer))
+        .collect();
+    assert_eq!(pairs, expected);
+    assert_eq!(props.layer_of("charset"), None);
+    // Applying the stack gives the same properties without the layers.
+    let mut applied = Properties::new();
+    stack().apply_to(&mut applied, "src/lib.rs").unwrap();
+    let applied: Vec<_> = applied.iter().map(|(k, v)| (k, v.as_str())).collect();
+    let resolved: Vec<_> = (props.properties().iter())
+        .map(|(k, v)| (k, v.as_str()))
+        .collect();
+    assert_eq!(applied, resolved);
+}
+
+#[test]
+fn global_file() {
+    let dir = TempTree::new("stack-global-file");
+    dir.write(
+        "editorconfig",
+        "[*.rs]\nindent_size = 2\n[src/*.md]\nindent_size = 3\n",
+    );
+    let path = dir.0.join("editorconfig");
+    let resolve = |target: &str| {
+        ConfigStack::new()
+            .with_global_file::<crate::glob::Glob>(&path)
+            .with_global_file::<crate::glob::Glob>(dir.0.join("nonexistent"))
+            .resolve(target)
+    };
+    let props = resolve("/project/src/lib.rs").unwrap();
+    assert_eq!(props.layer_of("indent_size"), Some(Layer::Global));
+    let props = resolve("/project/src/lib.md").unwrap();
+    assert_eq!(props.layer_of("indent_size"), None);
+    // Errors are reported with the line they occurred on.
+    dir.write("editorconfig", b"root = true\nindent_size = \xff\n");
+    let Err(crate::Error::Parse(_, Some(source))) = resolve("/project/src/lib.rs") else {
+        panic!("expected an error with a source");
+    };
+    assert_eq!(source.get().0, path);
+    assert_eq!(source.line(), Some(2));
+}
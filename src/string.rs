@@ -6,13 +6,14 @@ mod lowercase;
 pub use convert::*;
 pub(crate) use lowercase::into_lowercase;
 
-use std::borrow::Cow;
+use alloc::borrow::Cow;
+#[cfg(not(feature = "no_std"))]
 use std::path::Path;
 
 // Shared is a purely internal type alias.
 // Its usage requires it to implement From<T> and Deref<Target = T>.
 
-pub(crate) type Shared<T> = std::sync::Arc<T>;
+pub(crate) type Shared<T> = alloc::sync::Arc<T>;
 
 // TODO: Eventually add support for an Arc-like type that uses a thin pointer here.
 // Probably not triomphe::ThinArc, since we'd need to use unsafe to use it.
@@ -43,7 +44,7 @@ impl Default for SharedStringInner {
     }
 }
 
-impl std::ops::Deref for SharedStringInner {
+impl core::ops::Deref for SharedStringInner {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
@@ -90,22 +91,22 @@ impl PartialEq for SharedString {
 }
 impl Eq for SharedString {}
 impl PartialOrd for SharedString {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 impl Ord for SharedString {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.value.cmp(&other.value)
     }
 }
-impl std::hash::Hash for SharedString {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+impl core::hash::Hash for SharedString {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.value.get().hash(state);
     }
 }
 
-impl std::borrow::Borrow<str> for SharedString {
+impl core::borrow::Borrow<str> for SharedString {
     fn borrow(&self) -> &str {
         &self.value
     }
@@ -122,7 +123,7 @@ impl AsRef<[u8]> for SharedString {
     }
 }
 
-impl std::ops::Deref for SharedString {
+impl core::ops::Deref for SharedString {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
@@ -173,6 +174,7 @@ impl SharedString {
     /// Returns the [`Source`] of this string.
     ///
     /// If the `track-source` feature is not enabled, this function will always return `None`.
+    #[cfg(not(feature = "no_std"))]
     #[must_use]
     pub fn source(&self) -> Option<&Source> {
         #[cfg(feature = "track-source")]
@@ -188,6 +190,7 @@ impl SharedString {
     /// Sets the path and line number from which this value originated.
     ///
     /// If the `track-source` feature is not enabled, this function is a no-op.
+    #[cfg(not(feature = "no_std"))]
     pub fn set_source(&mut self, #[allow(unused)] source: Source) {
         #[cfg(feature = "track-source")]
         {
@@ -233,12 +236,27 @@ impl SharedString {
     }
 }
 
-impl std::fmt::Display for SharedString {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for SharedString {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", &*self.value)
     }
 }
 
+/// Serializes the string alone. Its [`Source`], if any, is not serialized.
+#[cfg(feature = "serde")]
+impl serde::Serialize for SharedString {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SharedString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        alloc::string::String::deserialize(deserializer).map(SharedString::new)
+    }
+}
+
 impl From<&str> for SharedString {
     fn from(value: &str) -> Self {
         Self::new(value)
@@ -247,28 +265,44 @@ impl From<&str> for SharedString {
 
 /// An immutable shared [`Path`] and line number for tracking the origins of strings and errors.
 ///
+/// This may also include the [`Span`][crate::Span] of the string within the file.
+///
+/// A source may also refer to a whole file rather than one of its lines,
+/// e.g. for an error opening the file. See [`Source::for_file`].
+///
 /// This type assumes that line numbers shall not exceed the maximum value of `usize`.
+#[cfg(not(feature = "no_std"))]
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Source {
     path: crate::string::Shared<Path>,
     line: usize,
+    span: Option<crate::Span>,
 }
 
-impl std::fmt::Debug for Source {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+#[cfg(not(feature = "no_std"))]
+impl core::fmt::Debug for Source {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let mut swriter = f.debug_struct("Source");
         swriter.field("path", &self.path);
         swriter.field("line", &self.line);
+        if let Some(span) = self.span.as_ref() {
+            swriter.field("span", span);
+        }
         swriter.finish()
     }
 }
 
-impl std::fmt::Display for Source {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}:{}", self.path.to_string_lossy(), self.line)
+#[cfg(not(feature = "no_std"))]
+impl core::fmt::Display for Source {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.line() {
+            Some(line) => write!(f, "{}:{}", self.path.to_string_lossy(), line),
+            None => write!(f, "{}", self.path.to_string_lossy()),
+        }
     }
 }
 
+#[cfg(not(feature = "no_std"))]
 impl Source {
     /// Constructs a new [`Source`] from the provided path and line number.
     ///
@@ -279,9 +313,36 @@ impl Source {
         Source {
             path: crate::string::Shared::from(path.as_ref()),
             line,
+            span: None,
         }
     }
+    /// Constructs a new [`Source`] that refers to the whole file at the provided path
+    /// rather than to one of its lines.
+    #[must_use]
+    pub fn for_file(path: &(impl AsRef<Path> + ?Sized)) -> Self {
+        Self::new(path, 0)
+    }
+    /// Returns the line number, or `None` if this refers to a whole file.
+    #[must_use]
+    pub fn line(&self) -> Option<usize> {
+        (self.line != 0).then_some(self.line)
+    }
+    /// Returns a version of `self` that also records the span of the string within the file.
+    #[must_use]
+    pub fn with_span(self, span: crate::Span) -> Self {
+        Source {
+            span: Some(span),
+            ..self
+        }
+    }
+    /// Returns the span of the string within the file, if it was recorded.
+    #[must_use]
+    pub fn span(&self) -> Option<crate::Span> {
+        self.span
+    }
     /// Returns a reference to the path and a copy of the line number.
+    ///
+    /// The line number is 0 if this refers to a whole file.
     #[must_use]
     pub fn get(&self) -> (&Path, usize) {
         (crate::string::Shared::as_ref(&self.path), self.line)
@@ -298,6 +359,7 @@ impl Source {
     }
 }
 
+#[cfg(not(feature = "no_std"))]
 impl AsRef<Path> for Source {
     fn as_ref(&self) -> &Path {
         self.get().0
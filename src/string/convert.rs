@@ -1,5 +1,6 @@
 use crate::string::SharedString;
-use std::borrow::Cow;
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
 
 /// Specialized trait for types whose references can be converted to [`SharedString`]s
 /// or possibly [`str`]s.
@@ -100,12 +101,13 @@ pub struct ParseError<E> {
     pub string: SharedString,
 }
 
-impl<E: std::fmt::Display> std::fmt::Display for ParseError<E> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<E: core::fmt::Display> core::fmt::Display for ParseError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "parse error: {}", &self.error)
     }
 }
 
+#[cfg(not(feature = "no_std"))]
 impl<E: std::error::Error + 'static> std::error::Error for ParseError<E> {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         Some(&self.error)
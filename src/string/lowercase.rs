@@ -1,4 +1,5 @@
-use std::borrow::Cow;
+use alloc::borrow::Cow;
+use alloc::string::String;
 
 pub(crate) fn into_lowercase(string: &str) -> Cow<'_, str> {
     let mut lcit = string.chars().flat_map(char::to_lowercase);
@@ -3,3 +3,222 @@ fn version_string_matches_ints() {
     use crate::version::*;
     assert_eq!(STRING, format!("{}.{}.{}", MAJOR, MINOR, PATCH));
 }
+
+#[test]
+fn spec_version() {
+    use crate::version::*;
+    assert_eq!(SpecVersion::CURRENT.to_string(), STRING);
+    assert_eq!(
+        SpecVersion::default(),
+        SpecVersion::new(MAJOR, MINOR, PATCH)
+    );
+    let old = SpecVersion::new(0, 8, 0);
+    assert!(old < SpecVersion::new(0, 10, 0));
+    assert!(old.has_legacy_fallbacks());
+    assert!(!SpecVersion::CURRENT.has_legacy_fallbacks());
+    assert!(!old.is_standard_key("spelling_language"));
+    assert!(old.is_standard_key("indent_size"));
+    assert!(SpecVersion::CURRENT.is_standard_key("spelling_language"));
+    assert!(!SpecVersion::CURRENT.is_standard_key("max_line_length"));
+    assert!(!old.has_numeric_ranges());
+    assert!(!SpecVersion::new(0, 10, 0).has_numeric_ranges());
+    assert!(SpecVersion::new(0, 11, 0).has_numeric_ranges());
+
+    let cases = [
+        ("a ; b", Some("a"), Some("a ; b")),
+        ("a#b ;c", Some("a#b"), Some("a#b ;c")),
+        ("a\t# b", Some("a"), Some("a\t# b")),
+        ("# a", Some("# a"), Some("# a")),
+        ("", None, Some("")),
+    ];
+    for (value, old_value, current_value) in cases {
+        assert_eq!(old.read_value(value), old_value, "{value:?}");
+        assert_eq!(SpecVersion::CURRENT.read_value(value), current_value);
+    }
+    assert_eq!(SpecVersion::new(0, 16, 0).read_value(""), None);
+    assert_eq!(SpecVersion::new(0, 14, 0).read_value("a ;"), Some("a"));
+}
+
+#[cfg(not(feature = "no_std"))]
+#[test]
+fn pattern_default_matches() {
+    use crate::Section;
+    use std::path::Path;
+
+    /// A pattern that only implements `matches_str`, matching paths equal to the pattern.
+    struct Exact(String);
+
+    impl crate::glob::Pattern for Exact {
+        type Error = std::convert::Infallible;
+
+        fn parse(pattern: &str) -> Result<Self, Self::Error> {
+            Ok(Exact(pattern.to_owned()))
+        }
+
+        fn matches_str(&self, path: &str) -> bool {
+            self.0 == path
+        }
+    }
+
+    let section = Section::<Exact>::new("/a/b");
+    assert!(section.applies_to("/a/b"));
+    assert!(section.applies_to("/a//./b/"));
+    assert!(!section.applies_to("a/b"));
+    assert!(section.applies_to_str("/a/b"));
+    assert!(Section::<Exact>::new("a/../b").applies_to(Path::new("a/../b")));
+    assert!(Section::<Exact>::new("/c").applies_to(Path::new("./c")));
+}
+
+#[cfg(all(feature = "ec4rs_glob", not(feature = "no_std")))]
+#[test]
+fn pattern_try_matches() {
+    use crate::glob::{Glob, MatchError, Pattern};
+    use std::path::Path;
+
+    let glob = <Glob as Pattern>::parse("src/**/*.rs").unwrap();
+    assert!(Pattern::try_matches(&glob, Path::new("src/a/lib.rs")).unwrap());
+    assert!(!Pattern::try_matches(&glob, Path::new("tests/lib.rs")).unwrap());
+    assert!(Pattern::may_match_under(&glob, Path::new("src/a")));
+    assert!(!Pattern::may_match_under(&glob, Path::new("tests")));
+    let error = MatchError::Other("bad pattern".into());
+    assert_eq!(error.to_string(), "bad pattern");
+    assert!(std::error::Error::source(&error).is_some());
+}
+
+#[cfg(feature = "ec4rs_glob")]
+#[test]
+fn applies_to_str() {
+    let section = crate::Section::<crate::glob::Glob>::new("src/*.rs");
+    assert!(section.applies_to_str("/src/lib.rs"));
+    assert!(section.applies_to_str("src//lib.rs"));
+    assert!(!section.applies_to_str("/x/src/lib.rs"));
+    #[cfg(not(feature = "no_std"))]
+    assert!(section.applies_to("/src/lib.rs"));
+}
+
+#[cfg(all(feature = "serde", not(feature = "no_std")))]
+#[test]
+fn serde_section() {
+    use crate::glob::Pattern;
+    use crate::property::UnknownValueError;
+    use crate::version::SpecVersion;
+    use crate::Section;
+
+    /// A pattern that fails to parse if it contains a `{`,
+    /// or a `[` before version 0.9.0.
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct NoBraces(String);
+
+    impl Pattern for NoBraces {
+        type Error = UnknownValueError;
+
+        fn parse(pattern: &str) -> Result<Self, Self::Error> {
+            if pattern.contains('{') {
+                Err(UnknownValueError)
+            } else {
+                Ok(NoBraces(pattern.to_owned()))
+            }
+        }
+
+        fn parse_with_version(pattern: &str, version: SpecVersion) -> Result<Self, Self::Error> {
+            if pattern.contains('[') && version < SpecVersion::new(0, 9, 0) {
+                Err(UnknownValueError)
+            } else {
+                Self::parse(pattern)
+            }
+        }
+
+        fn matches_str(&self, path: &str) -> bool {
+            self.0 == path
+        }
+    }
+
+    let mut section = Section::<NoBraces>::new("/a");
+    section.insert("Indent_Style", "tab");
+    section.insert("indent_size", "4");
+    let json = serde_json::to_string(&section).unwrap();
+    assert_eq!(
+        json,
+        r#"{"pattern":{"Ok":"/a"},"version":{"major":0,"minor":17,"patch":2},"props":{"indent_style":"tab","indent_size":"4"}}"#
+    );
+    let loaded: Section<NoBraces> = serde_json::from_str(&json).unwrap();
+    assert!(loaded.applies_to("/a"));
+    let pairs: Vec<_> = loaded.props().iter().collect();
+    assert_eq!(pairs.len(), 2);
+    assert_eq!(pairs[0].0, "indent_style");
+    assert_eq!(pairs[1].1.as_str(), "4");
+
+    let section = Section::<NoBraces>::new("*.{c,h}");
+    let json = serde_json::to_string(&section).unwrap();
+    assert_eq!(
+        json,
+        r#"{"pattern":{"Err":"*.{c,h}"},"version":{"major":0,"minor":17,"patch":2},"props":{}}"#
+    );
+    let loaded: Section<NoBraces> = serde_json::from_str(&json).unwrap();
+    let error = loaded
+        .or_err()
+        .err()
+        .expect("the parse error should be preserved");
+    assert_eq!(error.string.as_str(), "*.{c,h}");
+
+    // The pattern is parsed again with the version it failed with.
+    let section = Section::<NoBraces>::new_with_version("[ab]", SpecVersion::new(0, 8, 0));
+    let json = serde_json::to_string(&section).unwrap();
+    let loaded: Section<NoBraces> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.version(), SpecVersion::new(0, 8, 0));
+    assert!(loaded.or_err().is_err());
+}
+
+#[cfg(all(feature = "serde", feature = "ec4rs_glob"))]
+#[test]
+fn serde_glob_section() {
+    use crate::{glob::Glob, Section};
+    let mut section = Section::<Glob>::new("src/**/*.{rs,toml}");
+    section.insert("indent_size", "4");
+    let json = serde_json::to_string(&section).unwrap();
+    let loaded: Section<Glob> = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        loaded.pattern().as_ref().ok(),
+        section.pattern().as_ref().ok()
+    );
+    assert!(loaded.applies_to_str("/src/a/lib.rs"));
+    assert!(!loaded.applies_to_str("/lib.rs"));
+    assert_eq!(
+        loaded
+            .props()
+            .get_raw_for_key("indent_size")
+            .unwrap()
+            .as_str(),
+        "4"
+    );
+}
+
+/// A directory under the system's temporary directory that is removed when dropped.
+#[cfg(all(not(feature = "no_std"), feature = "ec4rs_glob"))]
+pub(crate) struct TempTree(pub std::path::PathBuf);
+
+#[cfg(all(not(feature = "no_std"), feature = "ec4rs_glob"))]
+impl TempTree {
+    /// Creates an empty directory that is unique to the test `name` and this process.
+    pub fn new(name: &str) -> TempTree {
+        let dir = std::env::temp_dir().join(format!("ec4rs-{name}-{}", std::process::id()));
+        // Remove anything left behind by a previous process with the same ID.
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        TempTree(std::fs::canonicalize(dir).unwrap())
+    }
+
+    /// Writes a file at `path` relative to the tree, creating its parent directories.
+    pub fn write(&self, path: &str, contents: impl AsRef<[u8]>) {
+        let path = self.0.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+}
+
+#[cfg(all(not(feature = "no_std"), feature = "ec4rs_glob"))]
+impl Drop for TempTree {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
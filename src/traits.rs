@@ -3,7 +3,7 @@ use crate::string::SharedString;
 /// Trait for types that be converted to [`SharedString`]s
 /// and parsed back out of the returned [`SharedString`]s.
 pub trait PropertyValue:
-    Sized + std::str::FromStr + crate::string::ToSharedString + Default
+    Sized + core::str::FromStr + crate::string::ToSharedString + Default
 {
     /// Parses a value from a [`SharedString`].
     ///
@@ -14,7 +14,7 @@ pub trait PropertyValue:
     /// it should not have a source.
     /// See [`SharedString::clear_source`].
     fn from_shared_string(value: &SharedString) -> Result<Self, Self::Err> {
-        std::str::FromStr::from_str(value)
+        core::str::FromStr::from_str(value)
     }
 }
 
@@ -34,9 +34,9 @@ pub trait PropertyKey {
 /// # Panics
 /// Panics if the initial and result values are not equal.
 #[cfg(test)]
-pub fn test_reparse<T, E: std::fmt::Debug>(initial: &T)
+pub fn test_reparse<T, E: core::fmt::Debug>(initial: &T)
 where
-    T: Clone + PropertyValue<Err = E> + std::fmt::Debug + PartialEq,
+    T: Clone + PropertyValue<Err = E> + core::fmt::Debug + PartialEq,
 {
     let written: SharedString = initial.clone().to_shared_string();
     let result = T::from_shared_string(&written).expect("reparse errored");
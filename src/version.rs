@@ -10,3 +10,118 @@ pub static STRING: &str = "0.17.2";
 pub static MAJOR: usize = 0;
 pub static MINOR: usize = 17;
 pub static PATCH: usize = 2;
+
+/// A version of the EditorConfig specification.
+///
+/// This can be passed to [`ConfigParser::with_version`][crate::ConfigParser::with_version]
+/// and [`Properties::use_fallbacks_for`][crate::Properties::use_fallbacks_for]
+/// to reproduce the documented behavior of older versions.
+/// Versions newer than [`SpecVersion::CURRENT`] behave as it does.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpecVersion {
+    major: usize,
+    minor: usize,
+    patch: usize,
+}
+
+impl SpecVersion {
+    /// The latest version that this library is known to be compliant with.
+    pub const CURRENT: SpecVersion = SpecVersion::new(0, 17, 2);
+
+    /// Constructs a version from its components.
+    #[must_use]
+    pub const fn new(major: usize, minor: usize, patch: usize) -> Self {
+        SpecVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Returns `true` if `indent_style = tab` does not imply `indent_size = tab`,
+    /// as was the case before 0.9.0.
+    #[must_use]
+    pub fn has_legacy_fallbacks(&self) -> bool {
+        *self < SpecVersion::new(0, 9, 0)
+    }
+
+    /// Returns `true` if `{num1..num2}` in a section header matches a range of integers,
+    /// which it has since 0.11.0. Before then, it matched itself as literal text.
+    #[must_use]
+    pub fn has_numeric_ranges(&self) -> bool {
+        *self >= SpecVersion::new(0, 11, 0)
+    }
+
+    /// Returns `true` if a `#` or `;` after whitespace in a value starts a comment,
+    /// as was the case before 0.15.0.
+    #[must_use]
+    pub fn has_inline_comments(&self) -> bool {
+        *self < SpecVersion::new(0, 15, 0)
+    }
+
+    /// Returns `true` if `spelling_language` is a standard property,
+    /// which it has been since 0.16.0.
+    #[must_use]
+    pub fn has_spelling_language(&self) -> bool {
+        *self >= SpecVersion::new(0, 16, 0)
+    }
+
+    /// Returns `true` if key-value pairs with empty values are kept,
+    /// which they have been since 0.17.0.
+    #[must_use]
+    pub fn has_empty_values(&self) -> bool {
+        *self >= SpecVersion::new(0, 17, 0)
+    }
+
+    /// Returns `true` if `key` is a standard property in this version.
+    ///
+    /// See [`lookup_standard_key`][crate::property::lookup_standard_key].
+    #[must_use]
+    pub fn is_standard_key(&self, key: &str) -> bool {
+        match crate::property::lookup_standard_key(key) {
+            Some("spelling_language") => self.has_spelling_language(),
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Returns the value of a key-value pair as this version would read it,
+    /// or `None` if this version ignores the pair.
+    pub(crate) fn read_value<'a>(&self, value: &'a str) -> Option<&'a str> {
+        let value = if self.has_inline_comments() {
+            strip_inline_comment(value)
+        } else {
+            value
+        };
+        if value.is_empty() && !self.has_empty_values() {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+impl Default for SpecVersion {
+    fn default() -> Self {
+        SpecVersion::CURRENT
+    }
+}
+
+impl core::fmt::Display for SpecVersion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Removes a comment starting with `#` or `;` after whitespace from the end of `value`.
+fn strip_inline_comment(value: &str) -> &str {
+    let mut prev_space = false;
+    for (idx, c) in value.char_indices() {
+        if prev_space && (c == '#' || c == ';') {
+            return value[..idx].trim_end();
+        }
+        prev_space = c.is_whitespace();
+    }
+    value
+}
@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 
 use clap::Parser;
-use semver::{Version, VersionReq};
+use ec4rs::version::SpecVersion;
+use ec4rs::PropertiesSource;
+use semver::Version;
 
 #[derive(Parser)]
 struct DisplayArgs {
@@ -24,7 +26,7 @@ struct Args {
     /// Override config filename
     #[clap(short)]
     filename: Option<PathBuf>,
-    /// Mostly ignored by this implementation
+    /// Emulate the specified version of EditorConfig
     #[clap(default_value = ec4rs::version::STRING, short = 'b')]
     ec_version: Version,
     /// Print test-friendly version information
@@ -42,22 +44,30 @@ fn print_empty_prefix(display: &DisplayArgs) {
     }
 }
 
+fn properties_of(
+    path: &std::path::Path,
+    filename: Option<&PathBuf>,
+    version: SpecVersion,
+) -> Result<ec4rs::Properties, ec4rs::Error> {
+    let mut props = ec4rs::Properties::new();
+    ec4rs::ConfigFiles::<ec4rs::glob::Glob>::open(path, filename)?
+        .with_version(version)
+        .apply_to(&mut props, path)?;
+    props.use_fallbacks_for(version);
+    Ok(props)
+}
+
 fn print_config(
     path: &std::path::Path,
     filename: Option<&PathBuf>,
-    legacy_fallbacks: bool,
+    version: SpecVersion,
     display: &DisplayArgs,
 ) {
-    match ec4rs::properties_from_config_of::<ec4rs::glob::Glob>(path, filename) {
-        Ok(mut props) => {
-            if legacy_fallbacks {
-                props.use_fallbacks_legacy();
-            } else {
-                props.use_fallbacks();
-            }
+    match properties_of(path, filename, version) {
+        Ok(props) => {
             for (key, value) in props.iter() {
                 let mut lc_value: Option<ec4rs::string::SharedString> = None;
-                let value_ref = if ec4rs::property::STANDARD_KEYS.contains(&key) {
+                let value_ref = if version.is_standard_key(key) {
                     lc_value.get_or_insert(value.into_lowercase())
                 } else {
                     value
@@ -84,9 +94,18 @@ fn print_config(
     }
 }
 
+fn spec_version(version: &Version) -> SpecVersion {
+    let component = |n: u64| usize::try_from(n).unwrap_or(usize::MAX);
+    SpecVersion::new(
+        component(version.major),
+        component(version.minor),
+        component(version.patch),
+    )
+}
+
 fn main() {
     let args = Args::parse();
-    let legacy_ver = VersionReq::parse("<0.9.0").unwrap();
+    let version = spec_version(&args.ec_version);
     if args.version {
         println!(
             "EditorConfig (ec4rs-parse {}) Version {}",
@@ -97,19 +116,14 @@ fn main() {
         print_config(
             args.files.first().unwrap(),
             args.filename.as_ref(),
-            legacy_ver.matches(&args.ec_version),
+            version,
             &args.display,
         );
     } else {
         for path in args.files {
             print_empty_prefix(&args.display);
             println!("[{}]", path.to_string_lossy());
-            print_config(
-                &path,
-                args.filename.as_ref(),
-                legacy_ver.matches(&args.ec_version),
-                &args.display,
-            );
+            print_config(&path, args.filename.as_ref(), version, &args.display);
         }
     }
 }